@@ -12,14 +12,7 @@ async fn main() {
         .add_item("https://getpocket.com/developer/docs/v3/add")
         .await
         .unwrap();
-    let item_id: i64 = resp
-        .item
-        .get("item_id")
-        .unwrap()
-        .as_str()
-        .unwrap()
-        .parse::<i64>()
-        .unwrap();
+    let item_id: i64 = resp.item.item_id;
 
     let resp_archive = get_pocket.archive(item_id).await;
     assert!(resp_archive.is_ok());
@@ -0,0 +1,351 @@
+//! Authorization of a user.
+//!
+//! A web application sends the user to Pocket and completes the authorization
+//! when the redirect arrives, keeping the request token in between:
+//!
+//! ```ignore
+//! let request = get_pocket.request_authorization("https://example.com/pocket/callback").await?;
+//! session.insert("pocket_code", &request.code);
+//! // redirect the user to request.authorize_url
+//!
+//! // in the handler of https://example.com/pocket/callback
+//! let AccessToken { access_token, username } = get_pocket
+//!     .complete_authorization(&session.get("pocket_code"))
+//!     .await?;
+//! ```
+//!
+//! Programs running on the user's machine can wait for the redirect on a local listener:
+//!
+//! ```ignore
+//! let get_pocket = GetPocket::builder().consumer_key(consumer_key).build()?;
+//!
+//! let get_pocket = auth::loopback_login(get_pocket, |auth_url| {
+//!     webbrowser::open(auth_url).map_err(|err| getpocket::Error::Custom(Box::new(err)))
+//! })
+//! .await?;
+//! ```
+use crate::{
+    client::GetPocket,
+    error::{Error, Result},
+    retrieving::{ListQuery, RetrievingExt},
+    secret::Secret,
+    transport::HttpRequest,
+    RecordItemDetailType,
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt, future::Future, pin::Pin, time::Duration};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+const CALLBACK_PATH: &str = "/getpocket/callback";
+
+/// Time a connection to the listener has to send its request
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+const SUCCESS_PAGE: &str = "<!DOCTYPE html><html><body>\
+    <p>The authorization is complete, you can close this window.</p>\
+    </body></html>";
+
+/// First step of the authorization, see `GetPocket::request_authorization`
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthorizationRequest {
+    /// Request token to keep until `GetPocket::complete_authorization`
+    pub code: Secret,
+    /// Page where the user authorizes the application
+    pub authorize_url: String,
+}
+
+// `authorize_url` carries the request token as well
+impl fmt::Debug for AuthorizationRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let authorize_url = match self.authorize_url.split_once("request_token=") {
+            Some((url, rest)) => {
+                let rest = rest.find('&').map_or("", |idx| &rest[idx..]);
+                format!("{url}request_token={}{rest}", self.code)
+            }
+            None => self.authorize_url.clone(),
+        };
+
+        f.debug_struct("AuthorizationRequest")
+            .field("code", &self.code)
+            .field("authorize_url", &authorize_url)
+            .finish()
+    }
+}
+
+/// Token of an authorized user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessToken {
+    pub access_token: Secret,
+    pub username: String,
+}
+
+impl GetPocket {
+    /// Obtain a request token and the page where the user authorizes it,
+    /// Pocket redirects the user to `redirect_uri` afterwards.
+    /// <https://getpocket.com/developer/docs/authentication>
+    pub async fn request_authorization(&self, redirect_uri: &str) -> Result<AuthorizationRequest> {
+        #[derive(Serialize)]
+        struct RequestParams<'a> {
+            consumer_key: &'a str,
+            redirect_uri: &'a str,
+        }
+
+        #[derive(Deserialize)]
+        struct RequestCode {
+            code: String,
+        }
+
+        let params = RequestParams {
+            consumer_key: self.consumer_key.expose(),
+            redirect_uri,
+        };
+
+        let request = HttpRequest::post(self.endpoint("/v3/oauth/request")).json(&params)?;
+        let res = self.execute(request, true).await?;
+
+        // Not a `Decode` error, its body would carry the request token
+        let RequestCode { code } =
+            serde_json::from_slice(&res.body).map_err(|err| Error::Custom(Box::new(err)))?;
+
+        Ok(AuthorizationRequest {
+            authorize_url: self.authorize_url(&code, redirect_uri)?,
+            code: Secret::from(code),
+        })
+    }
+
+    /// Exchange the request token of `request_authorization` for the access token
+    /// once the user authorized it
+    pub async fn complete_authorization(&self, code: &str) -> Result<AccessToken> {
+        #[derive(Serialize)]
+        struct RequestParams<'a> {
+            consumer_key: &'a str,
+            code: &'a str,
+        }
+
+        let params = RequestParams {
+            consumer_key: self.consumer_key.expose(),
+            code,
+        };
+
+        let request = HttpRequest::post(self.endpoint("/v3/oauth/authorize")).json(&params)?;
+        // The request token can only be exchanged once, a repeated exchange is rejected
+        let res = self.execute(request, false).await?;
+
+        // Not a `Decode` error, its body would carry the access token
+        serde_json::from_slice(&res.body).map_err(|err| Error::Custom(Box::new(err)))
+    }
+}
+
+/// Result of `GetPocket::verify`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CredentialStatus {
+    /// The consumer key and the access token are accepted
+    Valid,
+    /// The client has no access token, the user must authorize first
+    MissingAccessToken,
+    /// The access token was revoked or does not belong to the consumer key
+    InvalidAccessToken { code: Option<u32>, message: String },
+    /// The consumer key is missing or unknown
+    InvalidConsumerKey { code: Option<u32>, message: String },
+    /// The credentials could not be checked, `reset` is the number of seconds until the limit resets
+    RateLimited { reset: Option<u64> },
+}
+
+impl CredentialStatus {
+    pub fn is_valid(&self) -> bool {
+        *self == CredentialStatus::Valid
+    }
+}
+
+/// `X-Error-Code` values of a missing or invalid consumer key
+const CONSUMER_KEY_ERROR_CODES: [u32; 2] = [138, 152];
+
+impl GetPocket {
+    /// Check the credentials with the cheapest authenticated request, a single simple item.
+    /// Failures unrelated to the credentials, such as a network error, are returned as `Err`.
+    pub async fn verify(&self) -> Result<CredentialStatus> {
+        if self.token.access_token.is_none() {
+            return Ok(CredentialStatus::MissingAccessToken);
+        }
+
+        let query = ListQuery::new()
+            .count(1)
+            .detail_type(RecordItemDetailType::Simple);
+
+        match self.list(&query).await {
+            Ok(_) => Ok(CredentialStatus::Valid),
+            Err(Error::Auth { code, message, .. })
+                if code.map_or(false, |code| CONSUMER_KEY_ERROR_CODES.contains(&code)) =>
+            {
+                Ok(CredentialStatus::InvalidConsumerKey { code, message })
+            }
+            Err(Error::Auth { code, message, .. }) => {
+                Ok(CredentialStatus::InvalidAccessToken { code, message })
+            }
+            // Only a 403 with an exhausted `X-Limit-*` quota, another 403 is not a rate limit
+            Err(Error::RateLimited { reset, .. }) => Ok(CredentialStatus::RateLimited { reset }),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+type Cancel = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Authorize the user with `LoopbackLogin`'s default settings
+pub async fn loopback_login<F>(get_pocket: GetPocket, opener: F) -> Result<GetPocket>
+where
+    F: FnOnce(&str) -> Result<()>,
+{
+    LoopbackLogin::new().login(get_pocket, opener).await
+}
+
+/// OAuth flow redirecting the browser to a temporary listener on 127.0.0.1.
+///
+/// `opener` receives the authorization page, usually to open it in the browser.
+/// Once Pocket redirects to the listener, the request token is exchanged for the access token.
+pub struct LoopbackLogin {
+    /// Port of the listener, any free port when 0
+    pub port: u16,
+    /// Longest time to wait for the user to authorize
+    pub timeout: Duration,
+    cancel: Option<Cancel>,
+}
+
+impl Default for LoopbackLogin {
+    fn default() -> Self {
+        Self {
+            port: 0,
+            timeout: Duration::from_secs(5 * 60),
+            cancel: None,
+        }
+    }
+}
+
+impl fmt::Debug for LoopbackLogin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoopbackLogin")
+            .field("port", &self.port)
+            .field("timeout", &self.timeout)
+            .field("cancel", &self.cancel.is_some())
+            .finish()
+    }
+}
+
+impl LoopbackLogin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Stop waiting for the redirect when `cancel` completes, e.g. on `tokio::signal::ctrl_c()`
+    pub fn with_cancel<C>(mut self, cancel: C) -> Self
+    where
+        C: Future + Send + 'static,
+    {
+        self.cancel = Some(Box::pin(async move {
+            cancel.await;
+        }));
+        self
+    }
+
+    pub async fn login<F>(self, mut get_pocket: GetPocket, opener: F) -> Result<GetPocket>
+    where
+        F: FnOnce(&str) -> Result<()>,
+    {
+        let listener = TcpListener::bind(("127.0.0.1", self.port))
+            .await
+            .map_err(|err| Error::Custom(Box::new(err)))?;
+        let port = listener
+            .local_addr()
+            .map_err(|err| Error::Custom(Box::new(err)))?
+            .port();
+
+        get_pocket.redirect_uri = format!("http://127.0.0.1:{port}{CALLBACK_PATH}");
+
+        let request = get_pocket
+            .request_authorization(&get_pocket.redirect_uri)
+            .await?;
+
+        opener(&request.authorize_url)?;
+
+        let cancel = self
+            .cancel
+            .unwrap_or_else(|| Box::pin(std::future::pending()));
+
+        tokio::select! {
+            res = tokio::time::timeout(self.timeout, wait_redirect(&listener)) => match res {
+                Ok(res) => res?,
+                Err(_) => return Err(Error::auth("Timed out waiting for the user to authorize.")),
+            },
+            _ = cancel => return Err(Error::auth("The authorization was cancelled.")),
+        }
+
+        get_pocket
+            .access_token_from_code(request.code.expose())
+            .await?;
+
+        Ok(get_pocket)
+    }
+}
+
+/// Accept connections until the browser is redirected to the callback
+async fn wait_redirect(listener: &TcpListener) -> Result<()> {
+    loop {
+        let (stream, _) = listener
+            .accept()
+            .await
+            .map_err(|err| Error::Custom(Box::new(err)))?;
+
+        // A broken or idle connection, e.g. a browser preconnect, is not the redirect
+        if let Ok(Ok(true)) =
+            tokio::time::timeout(CONNECTION_TIMEOUT, handle_connection(stream)).await
+        {
+            return Ok(());
+        }
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream) -> std::io::Result<bool> {
+    let mut reader = BufReader::new(&mut stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+
+    // Drain the headers, the body of a GET is empty
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+            break;
+        }
+    }
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+    let is_callback = path.split('?').next() == Some(CALLBACK_PATH);
+
+    let response = match is_callback {
+        true => format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{SUCCESS_PAGE}",
+            SUCCESS_PAGE.len()
+        ),
+        false => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            .to_string(),
+    };
+
+    stream.write_all(response.as_bytes()).await?;
+    let _ = stream.shutdown().await;
+
+    Ok(is_callback)
+}
@@ -0,0 +1,265 @@
+//! Synchronous client for programs without an async runtime.
+//!
+//! ```ignore
+//! use getpocket::blocking::GetPocket;
+//!
+//! let get_pocket = GetPocket::new(consumer_key, redirect_uri, access_token)?;
+//! let added = get_pocket.add_item("https://getpocket.com/developer/docs/v3/add")?;
+//! get_pocket.archive(added.item.item_id)?;
+//! ```
+//!
+//! Every call blocks the current thread on a runtime owned by the client,
+//! so it must not be made from inside an async context. The client may still be
+//! created and dropped there, the runtime is shut down without waiting for it.
+use crate::{
+    adding::{AddingExt, RecordAdded},
+    auth::CredentialStatus,
+    bulk::{BulkExt, BulkRecordModified, BulkRequestAdd},
+    client,
+    error::{Error, Result},
+    item::Item,
+    modifying::{
+        ModifyingExt, RecordModified, RequestArchive, RequestDelete, RequestFavorite, RequestReadd,
+        RequestUnfavorite,
+    },
+    retrieving::{ListQuery, RecordItem, RetrievingExt},
+    tags::{
+        self, RequestAddTags, RequestClearTags, RequestDeleteTags, RequestRemoveTags,
+        RequestRenameTags, RequestReplaceTags, TagsExt,
+    },
+    RecordItemContentType, RecordItemDetailType, RecordItemFavorite, RecordItemSort,
+    RecordItemState, RecordItemTag,
+};
+use futures::StreamExt;
+use serde::Serialize;
+use std::{fmt, future::Future, sync::Arc};
+use tokio::runtime::Runtime;
+
+/// Blocking wrapper of `getpocket::GetPocket`, its clones share the runtime
+#[derive(Debug, Clone)]
+pub struct GetPocket {
+    inner: client::GetPocket,
+    runtime: Arc<OwnedRuntime>,
+}
+
+/// Runtime of the last clone, dropping a runtime inside an async context panics
+/// unless it is shut down in the background
+struct OwnedRuntime(Option<Runtime>);
+
+impl fmt::Debug for OwnedRuntime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("OwnedRuntime").finish()
+    }
+}
+
+impl Drop for OwnedRuntime {
+    fn drop(&mut self) {
+        if let Some(runtime) = self.0.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+impl GetPocket {
+    pub fn new(consumer_key: String, redirect_uri: String, access_token: String) -> Result<Self> {
+        Self::from_async(
+            client::GetPocket::builder()
+                .consumer_key(consumer_key)
+                .redirect_uri(redirect_uri)
+                .access_token(access_token)
+                .build()?,
+        )
+    }
+
+    /// Wrap a client configured with `getpocket::GetPocket::builder`
+    pub fn from_async(inner: client::GetPocket) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|err| Error::Custom(Box::new(err)))?;
+
+        Ok(Self {
+            inner,
+            runtime: Arc::new(OwnedRuntime(Some(runtime))),
+        })
+    }
+
+    /// The async client, e.g. for its rate limits
+    pub fn inner(&self) -> &client::GetPocket {
+        &self.inner
+    }
+
+    /// See `getpocket::GetPocket::verify`
+    pub fn verify(&self) -> Result<CredentialStatus> {
+        self.block_on(self.inner.verify())
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        match &self.runtime.0 {
+            Some(runtime) => runtime.block_on(future),
+            None => unreachable!("the runtime is only taken on drop"),
+        }
+    }
+
+    pub fn list(&self, query: &ListQuery) -> Result<RecordItem> {
+        self.block_on(self.inner.list(query))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn list_of_items_with_params(
+        &self,
+        state: RecordItemState,
+        favorite: RecordItemFavorite,
+        tag: RecordItemTag<'_>,
+        content_type: RecordItemContentType,
+        sort: RecordItemSort,
+        detail_type: RecordItemDetailType,
+        search: Option<&str>,
+        domain: Option<&str>,
+        since: Option<&i32>,
+        offset: i32,
+        count: i32,
+    ) -> Result<RecordItem> {
+        self.block_on(self.inner.list_of_items_with_params(
+            state,
+            favorite,
+            tag,
+            content_type,
+            sort,
+            detail_type,
+            search,
+            domain,
+            since,
+            offset,
+            count,
+        ))
+    }
+
+    pub fn list_of_items_paginate(&self, offset: i32, count: i32) -> Result<RecordItem> {
+        self.block_on(self.inner.list_of_items_paginate(offset, count))
+    }
+
+    pub fn list_of_items(&self) -> Result<RecordItem> {
+        self.block_on(self.inner.list_of_items())
+    }
+
+    /// Iterator over every item matching the query, see `RetrievingExt::list_stream`
+    pub fn list_iter(&self, query: ListQuery) -> impl Iterator<Item = Result<Item>> + '_ {
+        let mut stream = self.inner.list_stream(query);
+
+        std::iter::from_fn(move || self.block_on(stream.next()))
+    }
+
+    pub fn add_item_with_params(
+        &self,
+        url: &str,
+        title: Option<&str>,
+        tags: Option<&[&str]>,
+        tweet_id: Option<&str>,
+    ) -> Result<RecordAdded> {
+        self.block_on(self.inner.add_item_with_params(url, title, tags, tweet_id))
+    }
+
+    pub fn add_item(&self, url: &str) -> Result<RecordAdded> {
+        self.block_on(self.inner.add_item(url))
+    }
+
+    pub fn archive(&self, item_id: i64) -> Result<RecordModified> {
+        self.block_on(self.inner.archive(item_id))
+    }
+
+    pub fn readd(&self, item_id: i64) -> Result<RecordModified> {
+        self.block_on(self.inner.readd(item_id))
+    }
+
+    pub fn favorite(&self, item_id: i64) -> Result<RecordModified> {
+        self.block_on(self.inner.favorite(item_id))
+    }
+
+    pub fn unfavorite(&self, item_id: i64) -> Result<RecordModified> {
+        self.block_on(self.inner.unfavorite(item_id))
+    }
+
+    pub fn delete(&self, item_id: i64) -> Result<RecordModified> {
+        self.block_on(self.inner.delete(item_id))
+    }
+
+    pub fn tags_add(&self, params: &RequestAddTags) -> Result<tags::RecordModified> {
+        self.block_on(self.inner.tags_add(params))
+    }
+
+    pub fn tags_remove(&self, params: &RequestRemoveTags) -> Result<tags::RecordModified> {
+        self.block_on(self.inner.tags_remove(params))
+    }
+
+    pub fn tags_replace(&self, params: &RequestReplaceTags) -> Result<tags::RecordModified> {
+        self.block_on(self.inner.tags_replace(params))
+    }
+
+    pub fn tags_clear(&self, params: &RequestClearTags) -> Result<tags::RecordModified> {
+        self.block_on(self.inner.tags_clear(params))
+    }
+
+    pub fn tag_rename(&self, params: &RequestRenameTags) -> Result<tags::RecordModified> {
+        self.block_on(self.inner.tag_rename(params))
+    }
+
+    pub fn tag_delete(&self, params: &RequestDeleteTags) -> Result<tags::RecordModified> {
+        self.block_on(self.inner.tag_delete(params))
+    }
+
+    pub fn bulk_modify<T>(&self, params: &[T]) -> Result<BulkRecordModified>
+    where
+        T: Serialize + Sync,
+    {
+        self.block_on(self.inner.bulk_modify(params))
+    }
+
+    pub fn bulk_add(&self, params: &[BulkRequestAdd]) -> Result<BulkRecordModified> {
+        self.block_on(self.inner.bulk_add(params))
+    }
+
+    pub fn bulk_archive(&self, params: &[RequestArchive]) -> Result<BulkRecordModified> {
+        self.block_on(self.inner.bulk_archive(params))
+    }
+
+    pub fn bulk_readd(&self, params: &[RequestReadd]) -> Result<BulkRecordModified> {
+        self.block_on(self.inner.bulk_readd(params))
+    }
+
+    pub fn bulk_favorite(&self, params: &[RequestFavorite]) -> Result<BulkRecordModified> {
+        self.block_on(self.inner.bulk_favorite(params))
+    }
+
+    pub fn bulk_unfavorite(&self, params: &[RequestUnfavorite]) -> Result<BulkRecordModified> {
+        self.block_on(self.inner.bulk_unfavorite(params))
+    }
+
+    pub fn bulk_delete(&self, params: &[RequestDelete]) -> Result<BulkRecordModified> {
+        self.block_on(self.inner.bulk_delete(params))
+    }
+
+    pub fn bulk_tags_add(&self, params: &[RequestAddTags]) -> Result<BulkRecordModified> {
+        self.block_on(self.inner.bulk_tags_add(params))
+    }
+
+    pub fn bulk_tags_remove(&self, params: &[RequestRemoveTags]) -> Result<BulkRecordModified> {
+        self.block_on(self.inner.bulk_tags_remove(params))
+    }
+
+    pub fn bulk_tags_replace(&self, params: &[RequestReplaceTags]) -> Result<BulkRecordModified> {
+        self.block_on(self.inner.bulk_tags_replace(params))
+    }
+
+    pub fn bulk_tags_clear(&self, params: &[RequestClearTags]) -> Result<BulkRecordModified> {
+        self.block_on(self.inner.bulk_tags_clear(params))
+    }
+
+    pub fn bulk_tag_rename(&self, params: &[RequestRenameTags]) -> Result<BulkRecordModified> {
+        self.block_on(self.inner.bulk_tag_rename(params))
+    }
+
+    pub fn bulk_tag_delete(&self, params: &[RequestDeleteTags]) -> Result<BulkRecordModified> {
+        self.block_on(self.inner.bulk_tag_delete(params))
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{
+    client::{GetPocket, DEFAULT_BASE_URL},
+    error::{Error, Result},
+    retry::RetryPolicy,
+    secret::Secret,
+    transport::{HttpTransport, ReqwestTransport},
+};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::{sync::Arc, time::Duration};
+
+/// Configuration of a `GetPocket` client and of its HTTP client.
+///
+/// ```ignore
+/// let get_pocket = GetPocket::builder()
+///     .consumer_key(consumer_key)
+///     .access_token(access_token)
+///     .timeout(Duration::from_secs(30))
+///     .user_agent("my-app/1.0")
+///     .build()?;
+/// ```
+#[derive(Debug, Default)]
+pub struct GetPocketBuilder {
+    consumer_key: Option<Secret>,
+    access_token: Option<Secret>,
+    username: Option<String>,
+    redirect_uri: Option<String>,
+    base_url: Option<String>,
+    client: Option<reqwest::Client>,
+    transport: Option<Arc<dyn HttpTransport>>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<reqwest::Proxy>,
+    user_agent: Option<String>,
+    headers: Vec<(String, String)>,
+    retry_policy: Option<RetryPolicy>,
+}
+
+impl GetPocketBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn consumer_key(mut self, consumer_key: impl Into<String>) -> Self {
+        self.consumer_key = Some(Secret::new(consumer_key));
+        self
+    }
+
+    /// Token of an authorized user, without it only the authorization requests can be sent
+    pub fn access_token(mut self, access_token: impl Into<String>) -> Self {
+        self.access_token = Some(Secret::new(access_token));
+        self
+    }
+
+    /// Pocket account of the access token, see `GetPocket::account`
+    pub fn username(mut self, username: impl Into<String>) -> Self {
+        self.username = Some(username.into());
+        self
+    }
+
+    pub fn redirect_uri(mut self, redirect_uri: impl Into<String>) -> Self {
+        self.redirect_uri = Some(redirect_uri.into());
+        self
+    }
+
+    /// See `GetPocket::with_base_url`
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Use a pre-built client, it cannot be combined with the other HTTP settings
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Send the requests through another HTTP stack, e.g. `MemoryTransport` in tests.
+    /// It cannot be combined with a client or the other HTTP settings.
+    pub fn transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Timeout of a whole request, from connecting until the body is read
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Header sent with every request
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// See `GetPocket::with_retry_policy`
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    pub fn build(self) -> Result<GetPocket> {
+        let consumer_key = self
+            .consumer_key
+            .ok_or_else(|| Error::InvalidParams("The consumer key is required.".to_string()))?;
+
+        let has_http_settings = self.timeout.is_some()
+            || self.connect_timeout.is_some()
+            || self.proxy.is_some()
+            || self.user_agent.is_some()
+            || !self.headers.is_empty();
+
+        let transport: Arc<dyn HttpTransport> = match (self.transport, self.client) {
+            (Some(_), Some(_)) => {
+                return Err(Error::InvalidParams(
+                    "A transport cannot be combined with a pre-built client.".to_string(),
+                ))
+            }
+            (Some(_), None) | (None, Some(_)) if has_http_settings => {
+                return Err(Error::InvalidParams(
+                    "A pre-built client or transport cannot be combined with timeouts, proxy, user agent or headers.".to_string(),
+                ))
+            }
+            (Some(transport), None) => transport,
+            (None, Some(client)) => Arc::new(ReqwestTransport::new(client)),
+            (None, None) => {
+                let mut headers = HeaderMap::new();
+
+                for (name, value) in &self.headers {
+                    let name = HeaderName::try_from(name.as_str())
+                        .map_err(|err| Error::InvalidParams(format!("Header `{name}`: {err}")))?;
+                    let value = HeaderValue::try_from(value.as_str())
+                        .map_err(|err| Error::InvalidParams(format!("Header `{name}`: {err}")))?;
+                    headers.insert(name, value);
+                }
+
+                let mut builder = reqwest::Client::builder().default_headers(headers);
+
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(connect_timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(connect_timeout);
+                }
+                if let Some(proxy) = self.proxy {
+                    builder = builder.proxy(proxy);
+                }
+                if let Some(user_agent) = &self.user_agent {
+                    let user_agent = HeaderValue::try_from(user_agent.as_str())
+                        .map_err(|err| Error::InvalidParams(format!("User agent: {err}")))?;
+                    builder = builder.user_agent(user_agent);
+                }
+
+                Arc::new(ReqwestTransport::new(builder.build()?))
+            }
+        };
+
+        let get_pocket = GetPocket::from_parts(
+            consumer_key,
+            self.redirect_uri.unwrap_or_default(),
+            self.access_token,
+            self.username,
+            transport,
+        )
+        .with_base_url(self.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL));
+
+        Ok(match self.retry_policy {
+            Some(retry_policy) => get_pocket.with_retry_policy(retry_policy),
+            None => get_pocket,
+        })
+    }
+}
@@ -0,0 +1,276 @@
+//! Offline mirror of the user's list in an embedded SQLite database.
+//!
+//! ```ignore
+//! let cache = Cache::open("pocket.sqlite")?;
+//! cache.refresh(&get_pocket).await?;
+//!
+//! for item in cache.items_by_tag("rust")? {
+//!     println!("{:?}", item.url());
+//! }
+//! ```
+use crate::{
+    client::GetPocket,
+    error::{Error, Result},
+    ext::sync::{SyncChanges, SyncExt, SyncState},
+    item::{Item, ItemStatus},
+    request::decode,
+};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::{path::Path, sync::Mutex};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS items (
+        item_id INTEGER PRIMARY KEY,
+        status INTEGER NOT NULL,
+        favorite INTEGER NOT NULL,
+        domain TEXT,
+        time_added INTEGER,
+        time_updated INTEGER,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS items_status ON items (status);
+    CREATE INDEX IF NOT EXISTS items_domain ON items (domain);
+
+    CREATE TABLE IF NOT EXISTS tags (
+        item_id INTEGER NOT NULL REFERENCES items (item_id) ON DELETE CASCADE,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (item_id, tag)
+    );
+    CREATE INDEX IF NOT EXISTS tags_tag ON tags (tag);
+
+    CREATE TABLE IF NOT EXISTS sync_state (
+        id INTEGER PRIMARY KEY CHECK (id = 0),
+        since INTEGER
+    );
+";
+
+pub struct Cache {
+    conn: Mutex<Connection>,
+}
+
+impl Cache {
+    /// Open or create the database file
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// A database living only as long as the returned value
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+        conn.execute_batch(SCHEMA)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Fetch the changes since the last refresh and store them.
+    /// The first refresh downloads the whole list.
+    pub async fn refresh(&self, get_pocket: &GetPocket) -> Result<SyncChanges> {
+        let mut state = self.sync_state()?;
+
+        let changes = get_pocket.sync(&mut state).await?;
+
+        self.apply(&changes)?;
+
+        Ok(changes)
+    }
+
+    /// Store the changes returned by `SyncExt::sync` and advance the sync cursor
+    pub fn apply(&self, changes: &SyncChanges) -> Result<()> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+
+        for item in changes
+            .added
+            .iter()
+            .chain(&changes.updated)
+            .chain(&changes.archived)
+        {
+            upsert_item(&tx, item)?;
+        }
+
+        for item_id in &changes.deleted {
+            tx.execute("DELETE FROM items WHERE item_id = ?1", params![item_id])?;
+        }
+
+        tx.execute(
+            "INSERT INTO sync_state (id, since) VALUES (0, ?1)
+             ON CONFLICT (id) DO UPDATE SET since = excluded.since",
+            params![changes.since],
+        )?;
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// The cursor of the last refresh
+    pub fn sync_state(&self) -> Result<SyncState> {
+        let since: Option<Option<i64>> = self
+            .conn()
+            .query_row("SELECT since FROM sync_state WHERE id = 0", [], |row| {
+                row.get(0)
+            })
+            .optional()?;
+
+        Ok(SyncState {
+            since: since.flatten(),
+        })
+    }
+
+    pub fn item(&self, item_id: i64) -> Result<Option<Item>> {
+        let data: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT data FROM items WHERE item_id = ?1",
+                params![item_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        data.map(decode).transpose()
+    }
+
+    pub fn items(&self) -> Result<Vec<Item>> {
+        self.query_items("SELECT data FROM items ORDER BY time_added DESC", params![])
+    }
+
+    pub fn items_by_tag(&self, tag: &str) -> Result<Vec<Item>> {
+        self.query_items(
+            "SELECT items.data FROM items
+             JOIN tags ON tags.item_id = items.item_id
+             WHERE tags.tag = ?1
+             ORDER BY items.time_added DESC",
+            params![tag],
+        )
+    }
+
+    /// Items whose url belongs to the domain, e.g. `rust-lang.org`
+    pub fn items_by_domain(&self, domain: &str) -> Result<Vec<Item>> {
+        let domain = normalize_domain(domain);
+
+        self.query_items(
+            "SELECT data FROM items
+             WHERE domain = ?1 OR substr(domain, -length(?1) - 1) = '.' || ?1
+             ORDER BY time_added DESC",
+            params![domain],
+        )
+    }
+
+    pub fn unread(&self) -> Result<Vec<Item>> {
+        self.items_by_status(ItemStatus::Unread)
+    }
+
+    pub fn archived(&self) -> Result<Vec<Item>> {
+        self.items_by_status(ItemStatus::Archived)
+    }
+
+    pub fn favorites(&self) -> Result<Vec<Item>> {
+        self.query_items(
+            "SELECT data FROM items WHERE favorite = 1 ORDER BY time_added DESC",
+            params![],
+        )
+    }
+
+    /// Every tag used by at least one item
+    pub fn tags(&self) -> Result<Vec<String>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare("SELECT DISTINCT tag FROM tags ORDER BY tag")?;
+        let tags = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+
+        Ok(tags)
+    }
+
+    pub fn len(&self) -> Result<usize> {
+        let len: i64 = self
+            .conn()
+            .query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0))?;
+
+        Ok(len as usize)
+    }
+
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    fn items_by_status(&self, status: ItemStatus) -> Result<Vec<Item>> {
+        self.query_items(
+            "SELECT data FROM items WHERE status = ?1 ORDER BY time_added DESC",
+            params![status.as_i64()],
+        )
+    }
+
+    fn query_items(&self, sql: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<Item>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt
+            .query_map(params, |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+
+        rows.into_iter().map(decode).collect()
+    }
+}
+
+fn upsert_item(tx: &rusqlite::Transaction, item: &Item) -> Result<()> {
+    tx.execute(
+        "INSERT INTO items (item_id, status, favorite, domain, time_added, time_updated, data)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT (item_id) DO UPDATE SET
+            status = excluded.status,
+            favorite = excluded.favorite,
+            domain = excluded.domain,
+            time_added = excluded.time_added,
+            time_updated = excluded.time_updated,
+            data = excluded.data",
+        params![
+            item.item_id,
+            item.status.as_i64(),
+            item.favorite,
+            item.url().map(domain_of),
+            item.time_added,
+            item.time_updated,
+            serde_json::to_string(item).map_err(|err| Error::InvalidParams(err.to_string()))?,
+        ],
+    )?;
+
+    tx.execute("DELETE FROM tags WHERE item_id = ?1", params![item.item_id])?;
+
+    for tag in item.tag_names() {
+        tx.execute(
+            "INSERT INTO tags (item_id, tag) VALUES (?1, ?2)",
+            params![item.item_id, tag],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Host of the url without the `www.` prefix
+fn domain_of(url: &str) -> String {
+    let host = url.split("://").nth(1).unwrap_or(url);
+    let host = host.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = host.rsplit('@').next().unwrap_or_default();
+    let host = host.split(':').next().unwrap_or_default();
+
+    normalize_domain(host)
+}
+
+fn normalize_domain(domain: &str) -> String {
+    let domain = domain.trim().to_lowercase();
+
+    match domain.strip_prefix("www.") {
+        Some(domain) => domain.to_string(),
+        None => domain,
+    }
+}
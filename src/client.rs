@@ -1,51 +1,39 @@
-#![allow(dead_code)]
-use crate::ApiRequestError;
-use anyhow::{bail, Result};
+use crate::{
+    auth::AccessToken,
+    builder::GetPocketBuilder,
+    error::{Error, Result},
+    rate_limit::{RateLimitInfo, Throttle},
+    request::{decode, handler_status},
+    retry::RetryPolicy,
+    secret::Secret,
+    transport::{HttpRequest, HttpResponse, HttpTransport},
+};
+use reqwest::{
+    header::{HeaderName, HeaderValue},
+    StatusCode, Url,
+};
 use serde::{Deserialize, Serialize};
-use serde_qs as qs;
-use thiserror::Error;
-
-pub static ENDPOINT: &'static str = "https://getpocket.com/v3/send";
-
-const RATE_LIMIT_HEADERS: [(&str, &str); 6] = [
-    ("X-Limit-User-Limit", "Current rate limit enforced per user"),
-    (
-        "X-Limit-User-Remaining",
-        "Number of calls remaining before hitting user's rate limit",
-    ),
-    (
-        "X-Limit-User-Reset",
-        "Seconds until user's rate limit resets",
-    ),
-    (
-        "X-Limit-Key-Limit",
-        "Current rate limit enforced per consumer key",
-    ),
-    (
-        "X-Limit-Key-Remaining",
-        "Number of calls remaining before hitting consumer key's rate limit",
-    ),
-    (
-        "X-Limit-Key-Reset:",
-        "Seconds until consumer key rate limit resets",
-    ),
-];
-
-#[derive(Debug, Error)]
-pub enum ClientError<'a> {
-    #[error("{0}")]
-    JsonError(serde_json::Error),
-    #[error("There was an issue with the parameters. `{0}`")]
-    InvalidParams(&'a str),
-    #[error("Access to this resource is restricted")]
-    AccessDenied,
-    #[error("Token authentication failed. Please check your token and try again.")]
-    TokenError,
-}
+use std::{
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+/// Base URL of the API when none is set with `GetPocket::with_base_url`
+pub const DEFAULT_BASE_URL: &str = "https://getpocket.com";
+
+static ENDPOINT: &str = "/v3/send";
+
+/// Calls rejected because of an exhausted quota are attempted at most this many times
+const THROTTLE_MAX_ATTEMPTS: u32 = 3;
+
+/// Wait before attempting a rejected call again when Pocket did not report the reset,
+/// doubled on every attempt
+const THROTTLE_MIN_BACKOFF: Duration = Duration::from_secs(1);
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StandardResponse {
     pub action_results: Vec<bool>,
+    #[serde(default, deserialize_with = "deserialize_action_errors")]
     pub action_errors: Vec<Option<String>>,
     pub status: i32,
 }
@@ -53,10 +41,36 @@ pub struct StandardResponse {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ExtendedResponse {
     pub action_results: serde_json::Value,
+    #[serde(default, deserialize_with = "deserialize_action_errors")]
     pub action_errors: Vec<Option<String>>,
     pub status: i32,
 }
 
+/// Pocket reports an action error either as a plain string or as an object
+/// like `{"message": "...", "type": "...", "code": 422}`.
+fn deserialize_action_errors<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Vec<Option<String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let action_errors: Option<Vec<serde_json::Value>> = Deserialize::deserialize(deserializer)?;
+
+    Ok(action_errors
+        .unwrap_or_default()
+        .into_iter()
+        .map(|error| match error {
+            serde_json::Value::Null => None,
+            serde_json::Value::String(message) => Some(message),
+            serde_json::Value::Object(ref object) => match object.get("message") {
+                Some(serde_json::Value::String(message)) => Some(message.clone()),
+                _ => Some(error.to_string()),
+            },
+            error => Some(error.to_string()),
+        })
+        .collect())
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 pub enum RecordSendDirect {
@@ -64,39 +78,79 @@ pub enum RecordSendDirect {
     Extended(ExtendedResponse),
 }
 
+impl RecordSendDirect {
+    /// Success flag of every action, in the order the actions were sent.
+    /// Extended results (e.g. the item returned by `add`) count as success.
+    pub fn action_results(&self) -> Vec<bool> {
+        match self {
+            RecordSendDirect::Standart(record) => record.action_results.clone(),
+            RecordSendDirect::Extended(record) => match &record.action_results {
+                serde_json::Value::Array(results) => results
+                    .iter()
+                    .map(|result| match result {
+                        serde_json::Value::Bool(is_success) => *is_success,
+                        serde_json::Value::Null => false,
+                        _ => true,
+                    })
+                    .collect(),
+                _ => vec![],
+            },
+        }
+    }
+
+    pub fn action_errors(&self) -> &[Option<String>] {
+        match self {
+            RecordSendDirect::Standart(record) => &record.action_errors,
+            RecordSendDirect::Extended(record) => &record.action_errors,
+        }
+    }
+
+    pub fn status(&self) -> i32 {
+        match self {
+            RecordSendDirect::Standart(record) => record.status,
+            RecordSendDirect::Extended(record) => record.status,
+        }
+    }
+}
+
 #[derive(Deserialize, Default, Clone, Debug)]
 pub struct Token {
-    pub code: Option<String>,
-    pub access_token: Option<String>,
+    pub code: Option<Secret>,
+    pub access_token: Option<Secret>,
+    /// Pocket account the access token belongs to
+    pub username: Option<String>,
 }
 
-impl<'a> Token {
+impl Token {
     fn new() -> Self {
         Self::default()
     }
 
     fn set_code(&mut self, code: &str) {
-        let code = code.to_string();
-        self.code = Some(code);
+        self.code = Some(Secret::from(code));
     }
 
     fn set_access_token(&mut self, access_token: &str) {
-        let access_token = access_token.to_string();
-        self.access_token = Some(access_token);
+        self.access_token = Some(Secret::from(access_token));
     }
-}
 
-#[derive(Debug, Clone)]
-pub struct Reqwester {
-    pub client: reqwest::Client,
+    fn set_username(&mut self, username: &str) {
+        let username = username.to_string();
+        self.username = Some(username);
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct GetPocket {
-    pub consumer_key: String,
+    pub consumer_key: Secret,
     pub redirect_uri: String,
     pub token: Token,
-    pub reqwester: Reqwester,
+    base_url: String,
+    transport: Arc<dyn HttpTransport>,
+    /// Shared between clones, they are rate limited together
+    rate_limit: Arc<Mutex<Option<RateLimitInfo>>>,
+    throttle: Option<Throttle>,
+    retry_policy: RetryPolicy,
 }
 
 impl GetPocket {
@@ -110,27 +164,21 @@ impl GetPocket {
         F: for<'a> FnOnce(&'a str),
         C: for<'b> FnOnce(&'b str) -> Result<bool>,
     {
-        let token = Token::new();
-
-        let reqwester = Self::init_reqwester();
-
-        let mut get_pocket = Self {
-            consumer_key,
-            redirect_uri,
-            reqwester,
-            token,
-        };
+        let mut get_pocket = Self::builder()
+            .consumer_key(consumer_key)
+            .redirect_uri(redirect_uri)
+            .build()?;
 
         get_pocket
             .get_access_token_manual_open(opener_fn, None)
             .await?;
 
         if let Some(ref access_token) = get_pocket.token.access_token {
-            store_fn(access_token);
+            store_fn(access_token.expose());
 
             Ok(get_pocket)
         } else {
-            bail!(ClientError::TokenError);
+            Err(Error::auth("No access token was received."))
         }
     }
 
@@ -139,20 +187,42 @@ impl GetPocket {
         redirect_uri: String,
         access_token: String,
     ) -> Result<Self> {
-        let mut token = Token::new();
+        Self::builder()
+            .consumer_key(consumer_key)
+            .redirect_uri(redirect_uri)
+            .access_token(access_token)
+            .build()
+    }
 
-        token.set_access_token(&access_token);
+    /// Configure the client and its HTTP client, see `GetPocketBuilder`
+    pub fn builder() -> GetPocketBuilder {
+        GetPocketBuilder::new()
+    }
 
-        let reqwester = Self::init_reqwester();
+    pub(crate) fn from_parts(
+        consumer_key: Secret,
+        redirect_uri: String,
+        access_token: Option<Secret>,
+        username: Option<String>,
+        transport: Arc<dyn HttpTransport>,
+    ) -> Self {
+        let mut token = Token::new();
+
+        token.access_token = access_token;
+        if let Some(username) = username {
+            token.set_username(&username);
+        }
 
-        let get_pocket = Self {
+        Self {
             consumer_key,
             redirect_uri,
-            reqwester,
             token,
-        };
-
-        Ok(get_pocket)
+            transport,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            rate_limit: Default::default(),
+            throttle: None,
+            retry_policy: RetryPolicy::none(),
+        }
     }
 
     pub async fn send<T>(&self, params: T) -> Result<RecordSendDirect>
@@ -167,11 +237,11 @@ impl GetPocket {
         }
 
         let access_token = match &self.token.access_token {
-            Some(access_token) => access_token,
-            None => bail!(ClientError::TokenError),
+            Some(access_token) => access_token.expose(),
+            None => return Err(Error::auth("No access token")),
         };
 
-        let consumer_key = &self.consumer_key;
+        let consumer_key = self.consumer_key.expose();
 
         let req_param = RequestParams {
             consumer_key,
@@ -179,61 +249,160 @@ impl GetPocket {
             actions: params,
         };
 
-        let urlencoded = qs::to_string(&req_param)?;
+        // Adding the same url twice creates a duplicate, every other action can be repeated
+        let is_idempotent = match serde_json::to_value(&req_param.actions)
+            .map_err(|err| Error::InvalidParams(err.to_string()))?
+        {
+            serde_json::Value::Array(actions) => !actions
+                .iter()
+                .any(|action| action.get("action").and_then(|a| a.as_str()) == Some("add")),
+            _ => true,
+        };
 
-        let params = format!("{ENDPOINT}?{urlencoded}");
+        let request = HttpRequest::post(self.endpoint(ENDPOINT)).json(&req_param)?;
+        let res = self.execute(request, is_idempotent).await?;
 
-        let client = &self.reqwester.client;
-        let res = client.post(&params).send().await?;
+        decode(res.text())
+    }
 
-        if let Err(err) = ApiRequestError::handler_status(res.status()) {
-            bail!(err);
-        }
+    /// Send the requests to another Pocket-compatible service, e.g. a local mock server.
+    /// The API paths such as `/v3/get` are appended to `base_url`.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
 
-        let res_body = &res.text().await?;
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
 
-        let res_ser: Result<RecordSendDirect, serde_json::Error> = serde_json::from_str(&res_body);
+    pub(crate) fn endpoint(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
 
-        match res_ser {
-            Ok(res_ser) => Ok(res_ser),
-            Err(err) => Err(ClientError::JsonError(err).into()),
-        }
+    /// Username of the Pocket account the client is authorized for,
+    /// known after an authorization or when set with `GetPocketBuilder::username`
+    pub fn account(&self) -> Option<&str> {
+        self.token.username.as_deref()
     }
 
-    async fn token_code(&mut self) -> Result<String> {
-        if let Some(access_token) = &self.token.access_token {
-            return Ok(access_token.clone());
-        }
+    /// Rate limits reported by the last response, shared by all clones of this client
+    pub fn last_rate_limit(&self) -> Option<RateLimitInfo> {
+        *self.lock_rate_limit()
+    }
 
-        let endpoint = "https://getpocket.com/v3/oauth/request";
+    /// Pace the calls of this client and its clones with the X-Limit headers, see `Throttle`
+    pub fn with_throttle(mut self, throttle: Throttle) -> Self {
+        self.throttle = Some(throttle);
+        self
+    }
 
-        #[derive(Serialize)]
-        struct RequestParams<'a> {
-            consumer_key: &'a str,
-            redirect_uri: &'a str,
-        }
+    fn lock_rate_limit(&self) -> MutexGuard<'_, Option<RateLimitInfo>> {
+        self.rate_limit
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
 
-        #[derive(Deserialize)]
-        struct RequestCode {
-            code: String,
-        }
+    /// Retry transient failures of every call, see `RetryPolicy`
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
 
-        let map = RequestParams {
-            consumer_key: &self.consumer_key,
-            redirect_uri: &self.redirect_uri,
-        };
+    /// Send the request, record its rate limits and fail on an error status.
+    /// Requests that are not idempotent are only retried if the policy allows it.
+    pub(crate) async fn execute(
+        &self,
+        request: HttpRequest,
+        is_idempotent: bool,
+    ) -> Result<HttpResponse> {
+        let request = request.with_header(
+            HeaderName::from_static("x-accept"),
+            HeaderValue::from_static("application/json"),
+        );
 
-        let client = &self.reqwester.client;
-        let res = client.post(endpoint).json(&map).send().await?;
+        let mut attempts = 0;
+        let mut throttle_attempts = 0;
+
+        loop {
+            attempts += 1;
+
+            if let Some(throttle) = &self.throttle {
+                let wait = throttle.acquire(&mut self.lock_rate_limit());
+
+                if let Some(wait) = wait {
+                    if wait > throttle.max_wait {
+                        return Err(Error::RateLimited {
+                            status: None,
+                            reset: Some(wait.as_secs()),
+                            code: None,
+                            message: "Waiting for the reset would exceed the throttle's max_wait."
+                                .to_string(),
+                        });
+                    }
+
+                    tokio::time::sleep(wait).await;
+                }
+            }
+
+            let res = match self.transport.send(request.clone()).await {
+                Ok(res) => res,
+                Err(err) => match self
+                    .retry_policy
+                    .delay_on_error(&err, attempts, is_idempotent)
+                {
+                    Some(delay) => {
+                        tokio::time::sleep(delay).await;
+                        continue;
+                    }
+                    None => return Err(err),
+                },
+            };
+
+            let rate_limit = RateLimitInfo::from_headers(&res.headers);
+            if rate_limit.is_some() {
+                *self.lock_rate_limit() = rate_limit;
+            }
+
+            let exhausted_reset = rate_limit.and_then(|rate_limit| rate_limit.exhausted_reset());
+            let throttle = self
+                .throttle
+                .filter(|_| res.status == StatusCode::FORBIDDEN && exhausted_reset.is_some());
+
+            if let Some(throttle) = throttle {
+                throttle_attempts += 1;
+
+                if throttle_attempts < THROTTLE_MAX_ATTEMPTS {
+                    // `Throttle::acquire` waits for a known reset, otherwise back off
+                    if exhausted_reset.flatten().unwrap_or_default() == 0 {
+                        let backoff = THROTTLE_MIN_BACKOFF * 2u32.pow(throttle_attempts - 1);
+                        tokio::time::sleep(backoff.min(throttle.max_wait)).await;
+                    }
+
+                    continue;
+                }
+            } else if let Some(delay) =
+                self.retry_policy
+                    .delay_on_status(res.status, attempts, is_idempotent)
+            {
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+
+            handler_status(res.status, &res.headers, rate_limit.as_ref())?;
+
+            return Ok(res);
+        }
+    }
 
-        if let Err(err) = ApiRequestError::handler_status(res.status()) {
-            bail!(err);
+    async fn token_code(&mut self) -> Result<String> {
+        if let Some(access_token) = &self.token.access_token {
+            return Ok(access_token.expose().to_string());
         }
 
-        res.json::<RequestCode>()
+        self.request_authorization(&self.redirect_uri)
             .await
-            .map(|request_code| request_code.code)
-            .map_err(Into::into)
+            .map(|request| request.code.expose().to_string())
     }
 
     async fn get_access_token_manual_open<F>(
@@ -246,87 +415,56 @@ impl GetPocket {
     {
         let code = self.token_code().await?;
 
-        let redirect_uri = match redirect_uri {
-            Some(redirect_uri) => redirect_uri,
-            None => "https://getpocket.com",
-        };
+        let redirect_uri = redirect_uri.unwrap_or("https://getpocket.com");
 
-        let is_save = f(&format!(
-            "https://getpocket.com/auth/authorize?request_token={code}&redirect_uri={redirect_uri}"
-        ))?;
+        let is_save = f(&self.authorize_url(&code, redirect_uri)?)?;
 
         if is_save {
-            self.token.set_code(&code);
-            self.get_request_access_token().await?;
+            self.access_token_from_code(&code).await?;
 
             Ok(self)
         } else {
-            bail!(ClientError::InvalidParams(
-                "No token was provided from the callback function."
-            ));
+            Err(Error::InvalidParams(
+                "No token was provided from the callback function.".to_string(),
+            ))
         }
     }
 
-    fn init_reqwester() -> Reqwester {
-        use reqwest::header;
-
-        let mut headers = header::HeaderMap::new();
-        headers.insert(
-            "Content-Type",
-            header::HeaderValue::from_static("application/json; charset=UTF-8"),
-        );
-        headers.insert(
-            "X-Accept",
-            header::HeaderValue::from_static("application/json"),
-        );
-
-        let client = reqwest::Client::builder()
-            .default_headers(headers)
-            .build()
-            .unwrap();
+    /// Page where the user authorizes the request token `code`
+    pub(crate) fn authorize_url(&self, code: &str, redirect_uri: &str) -> Result<String> {
+        // `redirect_uri` may have a query string of its own
+        Url::parse_with_params(
+            &self.endpoint("/auth/authorize"),
+            [("request_token", code), ("redirect_uri", redirect_uri)],
+        )
+        .map(String::from)
+        .map_err(|err| Error::InvalidParams(format!("Authorization page: {err}")))
+    }
 
-        Reqwester { client }
+    /// Exchange the authorized request token for the access token
+    pub(crate) async fn access_token_from_code(&mut self, code: &str) -> Result<&mut Self> {
+        self.token.set_code(code);
+        self.get_request_access_token().await
     }
 
     async fn get_request_access_token(&mut self) -> Result<&mut Self> {
-        let endpoint = "https://getpocket.com/v3/oauth/authorize";
-
-        #[derive(Debug, Serialize)]
-        struct RequestParams<'a> {
-            consumer_key: &'a str,
-            code: &'a str,
-        }
-
-        let map = match &self.token.code {
-            Some(code) => RequestParams {
-                consumer_key: &self.consumer_key,
-                code,
-            },
-            None => bail!(ClientError::TokenError),
+        let code = match &self.token.code {
+            Some(code) => code.expose().to_string(),
+            None => return Err(Error::auth("No request token code")),
         };
 
-        #[derive(Deserialize)]
-        struct RequestAccessToken {
-            access_token: String,
-        }
-
-        let client = &self.reqwester.client;
-        let res = client.post(endpoint).json(&map).send().await?;
-
-        if let Err(err) = ApiRequestError::handler_status(res.status()) {
-            bail!(err);
-        }
-
-        match res.json::<RequestAccessToken>().await {
-            Ok(RequestAccessToken { access_token }) => self.token.set_access_token(&access_token),
-            Err(err) => Err(err)?,
-        }
+        let AccessToken {
+            access_token,
+            username,
+        } = self.complete_authorization(&code).await?;
+        self.token.set_access_token(access_token.expose());
+        self.token.set_username(&username);
 
         Ok(self)
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RecordItemState {
     All,
     #[default]
@@ -334,7 +472,7 @@ pub enum RecordItemState {
     Archive,
 }
 
-#[derive(Default)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RecordItemFavorite {
     #[default]
     All,
@@ -342,7 +480,7 @@ pub enum RecordItemFavorite {
     Favorited,
 }
 
-#[derive(Default)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RecordItemTag<'a> {
     #[default]
     All,
@@ -350,7 +488,7 @@ pub enum RecordItemTag<'a> {
     Untagged,
 }
 
-#[derive(Default)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RecordItemContentType {
     #[default]
     All,
@@ -359,7 +497,7 @@ pub enum RecordItemContentType {
     Image,
 }
 
-#[derive(Default)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RecordItemSort {
     #[default]
     All,
@@ -369,7 +507,7 @@ pub enum RecordItemSort {
     Site,
 }
 
-#[derive(Default)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RecordItemDetailType {
     #[default]
     All,
@@ -0,0 +1,193 @@
+use crate::{
+    builder::GetPocketBuilder,
+    client::GetPocket,
+    error::{Error, Result},
+    retry::RetryPolicy,
+    secret::Secret,
+};
+use serde::Deserialize;
+use std::{env, fs, path::Path, str::FromStr, time::Duration};
+
+const ENV_CONSUMER_KEY: &str = "GET_POCKET_CONSUMER_KEY";
+const ENV_ACCESS_TOKEN: &str = "GET_POCKET_ACCESS_TOKEN";
+const ENV_REDIRECT_URL: &str = "GET_POCKET_REDIRECT_URL";
+const ENV_BASE_URL: &str = "GET_POCKET_BASE_URL";
+const ENV_TIMEOUT_SECS: &str = "GET_POCKET_TIMEOUT_SECS";
+const ENV_CONNECT_TIMEOUT_SECS: &str = "GET_POCKET_CONNECT_TIMEOUT_SECS";
+const ENV_RETRY_MAX_ATTEMPTS: &str = "GET_POCKET_RETRY_MAX_ATTEMPTS";
+const ENV_RETRY_BASE_DELAY_MS: &str = "GET_POCKET_RETRY_BASE_DELAY_MS";
+const ENV_RETRY_MAX_DELAY_MS: &str = "GET_POCKET_RETRY_MAX_DELAY_MS";
+
+/// Settings of `GetPocket::from_env` and `GetPocket::from_config`.
+///
+/// ```toml
+/// consumer_key = "1234-abcd1234abcd1234abcd1234"
+/// access_token = "5678defg-5678-defg-5678-defg56"
+/// redirect_uri = "https://example.com/callback"
+/// base_url = "https://getpocket.com"
+/// timeout_secs = 30
+/// connect_timeout_secs = 10
+///
+/// [retry]
+/// max_attempts = 3
+/// base_delay_ms = 500
+/// max_delay_ms = 30000
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub consumer_key: Option<Secret>,
+    pub access_token: Option<Secret>,
+    pub redirect_uri: Option<String>,
+    pub base_url: Option<String>,
+    pub timeout_secs: Option<u64>,
+    pub connect_timeout_secs: Option<u64>,
+    pub retry: Option<RetryConfig>,
+}
+
+/// Retries of `Config`, unset values keep the defaults of `RetryPolicy`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetryConfig {
+    pub max_attempts: Option<u32>,
+    pub base_delay_ms: Option<u64>,
+    pub max_delay_ms: Option<u64>,
+    pub jitter: Option<bool>,
+}
+
+impl Config {
+    /// Read the environment variables `GET_POCKET_CONSUMER_KEY`, `GET_POCKET_ACCESS_TOKEN`,
+    /// `GET_POCKET_REDIRECT_URL`, `GET_POCKET_BASE_URL`, `GET_POCKET_TIMEOUT_SECS`,
+    /// `GET_POCKET_CONNECT_TIMEOUT_SECS`, `GET_POCKET_RETRY_MAX_ATTEMPTS`,
+    /// `GET_POCKET_RETRY_BASE_DELAY_MS` and `GET_POCKET_RETRY_MAX_DELAY_MS`
+    pub fn from_env() -> Result<Self> {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Read a TOML file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+
+        let contents = fs::read_to_string(path)
+            .map_err(|err| Error::Config(format!("Cannot read {}: {err}", path.display())))?;
+
+        let config: Self = toml::from_str(&contents)
+            .map_err(|err| Error::Config(format!("{}: {err}", path.display())))?;
+
+        if is_blank(config.consumer_key.as_ref()) {
+            return Err(Error::Config(format!(
+                "`consumer_key` is missing in {}",
+                path.display()
+            )));
+        }
+
+        Ok(config)
+    }
+
+    /// Same as `from_env` with the variables looked up by `var`, e.g. from a map in tests
+    pub fn from_vars<F>(var: F) -> Result<Self>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        fn parse<T: FromStr>(var: &impl Fn(&str) -> Option<String>, name: &str) -> Result<Option<T>>
+        where
+            T::Err: std::fmt::Display,
+        {
+            var(name)
+                .map(|value| {
+                    value
+                        .trim()
+                        .parse()
+                        .map_err(|err| Error::Config(format!("{name} is invalid: {err}")))
+                })
+                .transpose()
+        }
+
+        let retry = RetryConfig {
+            max_attempts: parse(&var, ENV_RETRY_MAX_ATTEMPTS)?,
+            base_delay_ms: parse(&var, ENV_RETRY_BASE_DELAY_MS)?,
+            max_delay_ms: parse(&var, ENV_RETRY_MAX_DELAY_MS)?,
+            jitter: None,
+        };
+
+        let config = Self {
+            consumer_key: var(ENV_CONSUMER_KEY).map(Secret::from),
+            access_token: var(ENV_ACCESS_TOKEN).map(Secret::from),
+            redirect_uri: var(ENV_REDIRECT_URL),
+            base_url: var(ENV_BASE_URL),
+            timeout_secs: parse(&var, ENV_TIMEOUT_SECS)?,
+            connect_timeout_secs: parse(&var, ENV_CONNECT_TIMEOUT_SECS)?,
+            retry: (retry != RetryConfig::default()).then_some(retry),
+        };
+
+        if is_blank(config.consumer_key.as_ref()) {
+            return Err(Error::Config(format!(
+                "The environment variable {ENV_CONSUMER_KEY} is not set or empty"
+            )));
+        }
+
+        Ok(config)
+    }
+
+    /// A builder with every setting of the configuration
+    pub fn into_builder(self) -> Result<GetPocketBuilder> {
+        let consumer_key = match self.consumer_key {
+            Some(consumer_key) if !is_blank(Some(&consumer_key)) => consumer_key,
+            _ => return Err(Error::Config("`consumer_key` is missing".to_string())),
+        };
+
+        let mut builder = GetPocket::builder().consumer_key(consumer_key.expose());
+
+        if let Some(access_token) = self.access_token {
+            builder = builder.access_token(access_token.expose());
+        }
+        if let Some(redirect_uri) = self.redirect_uri {
+            builder = builder.redirect_uri(redirect_uri);
+        }
+        if let Some(base_url) = self.base_url {
+            builder = builder.base_url(base_url);
+        }
+        if let Some(timeout_secs) = self.timeout_secs {
+            builder = builder.timeout(Duration::from_secs(timeout_secs));
+        }
+        if let Some(connect_timeout_secs) = self.connect_timeout_secs {
+            builder = builder.connect_timeout(Duration::from_secs(connect_timeout_secs));
+        }
+        if let Some(retry) = self.retry {
+            let mut retry_policy = RetryPolicy::default();
+
+            if let Some(max_attempts) = retry.max_attempts {
+                retry_policy = retry_policy.with_max_attempts(max_attempts);
+            }
+            if let Some(base_delay_ms) = retry.base_delay_ms {
+                retry_policy = retry_policy.with_base_delay(Duration::from_millis(base_delay_ms));
+            }
+            if let Some(max_delay_ms) = retry.max_delay_ms {
+                retry_policy = retry_policy.with_max_delay(Duration::from_millis(max_delay_ms));
+            }
+            if let Some(jitter) = retry.jitter {
+                retry_policy = retry_policy.with_jitter(jitter);
+            }
+
+            builder = builder.retry_policy(retry_policy);
+        }
+
+        Ok(builder)
+    }
+}
+
+fn is_blank(secret: Option<&Secret>) -> bool {
+    secret.map_or(true, |secret| secret.expose().trim().is_empty())
+}
+
+impl GetPocket {
+    /// Build the client from the `GET_POCKET_*` environment variables, see `Config`
+    pub fn from_env() -> Result<Self> {
+        Config::from_env()?.into_builder()?.build()
+    }
+
+    /// Build the client from a TOML file, see `Config`
+    pub fn from_config<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Config::from_file(path)?.into_builder()?.build()
+    }
+}
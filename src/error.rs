@@ -0,0 +1,92 @@
+use thiserror::Error;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Every failure of the crate.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// The API answered with an error status.
+    /// `code` and `message` come from the `X-Error-Code` and `X-Error` headers when Pocket sends them.
+    #[error("Request has encountered an error. {status}{} - {message}", code.map(|code| format!(" (code {code})")).unwrap_or_default())]
+    Http {
+        status: u16,
+        code: Option<u32>,
+        message: String,
+    },
+    /// The consumer key or the access token was rejected or is missing.
+    /// `status` is `None` when the client failed before any response, e.g. without an access token.
+    #[error("Problem authenticating the user{}. {message}", code.map(|code| format!(" (code {code})")).unwrap_or_default())]
+    Auth {
+        status: Option<u16>,
+        code: Option<u32>,
+        message: String,
+    },
+    /// The rate limit is exhausted, `reset` is the number of seconds until it resets.
+    /// `code` and `message` come from the `X-Error-Code` and `X-Error` headers like in `Http`,
+    /// `status` is `None` when the `Throttle` gave up before sending the call.
+    #[error("Rate limit exceeded{}{}. {message}", code.map(|code| format!(" (code {code})")).unwrap_or_default(), reset.map(|reset| format!(", it resets in {reset} seconds")).unwrap_or_default())]
+    RateLimited {
+        status: Option<u16>,
+        reset: Option<u64>,
+        code: Option<u32>,
+        message: String,
+    },
+    /// Pocket answered 503, `code` and `message` come from the `X-Error-Code` and `X-Error` headers
+    #[error("Service unavailable{}. {message}", code.map(|code| format!(" (code {code})")).unwrap_or_default())]
+    Maintenance { code: Option<u32>, message: String },
+    /// The response body could not be deserialized
+    #[error("Cannot decode the response. {source}")]
+    Decode {
+        body: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("There was an issue with the parameters. `{0}`")]
+    InvalidParams(String),
+    /// A setting of `GetPocket::from_env` or `GetPocket::from_config` is missing or invalid
+    #[error("Invalid configuration. {0}")]
+    Config(String),
+    /// The request could not be sent or the response could not be read
+    #[error(transparent)]
+    Transport(#[from] reqwest::Error),
+    /// Failure of a custom `HttpTransport`
+    #[error(transparent)]
+    Custom(Box<dyn std::error::Error + Send + Sync>),
+    #[cfg(feature = "cache")]
+    #[error(transparent)]
+    Cache(#[from] rusqlite::Error),
+}
+
+impl Error {
+    pub(crate) fn auth(message: &str) -> Self {
+        Error::Auth {
+            status: None,
+            code: None,
+            message: message.to_string(),
+        }
+    }
+
+    /// HTTP status of the response that caused the error, if any
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Error::Http { status, .. } => Some(*status),
+            Error::Auth { status, .. } | Error::RateLimited { status, .. } => *status,
+            Error::Maintenance { .. } => Some(503),
+            Error::Transport(err) => err.status().map(|status| status.as_u16()),
+            _ => None,
+        }
+    }
+
+    /// Pocket's `X-Error-Code` of the response that caused the error, if any
+    /// <https://getpocket.com/developer/docs/errors>
+    pub fn code(&self) -> Option<u32> {
+        match self {
+            Error::Http { code, .. }
+            | Error::Auth { code, .. }
+            | Error::RateLimited { code, .. }
+            | Error::Maintenance { code, .. } => *code,
+            _ => None,
+        }
+    }
+}
@@ -1,21 +1,18 @@
-use crate::{client::GetPocket, ApiRequestError};
-use anyhow::{bail, format_err, Result};
+use crate::{
+    client::GetPocket,
+    error::{Error, Result},
+    item::Item,
+    request::decode,
+    transport::HttpRequest,
+};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap as Map;
-use thiserror::Error;
 
-static ENDPOINT: &'static str = "https://getpocket.com/v3/add";
-
-#[derive(Error, Debug)]
-pub enum AddingError<'a> {
-    #[error("Invalid Params: `{0}`")]
-    InvalidParams(&'a str),
-}
+static ENDPOINT: &str = "/v3/add";
 
 #[derive(Debug, Deserialize)]
 pub struct RecordAdded {
-    pub item: Map<String, serde_json::Value>,
+    pub item: Item,
     pub status: i32,
 }
 
@@ -56,28 +53,20 @@ impl AddingExt for GetPocket {
     ) -> Result<RecordAdded> {
         let params = match &self.token.access_token {
             Some(access_token) => RequestParams {
-                consumer_key: &self.consumer_key,
-                access_token,
+                consumer_key: self.consumer_key.expose(),
+                access_token: access_token.expose(),
                 url,
                 title,
                 tags,
                 tweet_id,
             },
-            None => bail!(AddingError::InvalidParams("No access_token")),
+            None => return Err(Error::auth("No access token")),
         };
 
-        let client = &self.reqwester.client;
-        let res = client.post(ENDPOINT).json(&params).send().await?;
-
-        if let Err(err) = ApiRequestError::handler_status(res.status()) {
-            bail!(err);
-        }
-
-        let res_body = &res.text().await?;
-
-        let res_ser: RecordAdded = serde_json::from_str(&res_body).map_err(|e| format_err!(e))?;
+        let request = HttpRequest::post(self.endpoint(ENDPOINT)).json(&params)?;
+        let res = self.execute(request, false).await?;
 
-        Ok(res_ser)
+        decode(res.text())
     }
 
     async fn add_item<'a>(&self, url: &'a str) -> Result<RecordAdded> {
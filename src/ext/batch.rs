@@ -0,0 +1,207 @@
+use crate::{
+    client::GetPocket,
+    error::{Error, Result},
+    ext::{bulk::BulkRequestAdd, modifying::*, tags::*},
+};
+use async_trait::async_trait;
+use serde::Serialize;
+
+/// Number of actions sent in a single /v3/send request by default.
+pub const DEFAULT_CHUNK_SIZE: usize = 50;
+
+/// Any action accepted by the /v3/send endpoint.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum BatchAction {
+    Add(BulkRequestAdd),
+    Archive(RequestArchive),
+    Readd(RequestReadd),
+    Favorite(RequestFavorite),
+    Unfavorite(RequestUnfavorite),
+    Delete(RequestDelete),
+    TagsAdd(RequestAddTags),
+    TagsRemove(RequestRemoveTags),
+    TagsReplace(RequestReplaceTags),
+    TagsClear(RequestClearTags),
+    TagRename(RequestRenameTags),
+    TagDelete(RequestDeleteTags),
+}
+
+macro_rules! impl_from_request {
+    ($($request:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$request> for BatchAction {
+                fn from(request: $request) -> Self {
+                    BatchAction::$variant(request)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_request! {
+    BulkRequestAdd => Add,
+    RequestArchive => Archive,
+    RequestReadd => Readd,
+    RequestFavorite => Favorite,
+    RequestUnfavorite => Unfavorite,
+    RequestDelete => Delete,
+    RequestAddTags => TagsAdd,
+    RequestRemoveTags => TagsRemove,
+    RequestReplaceTags => TagsReplace,
+    RequestClearTags => TagsClear,
+    RequestRenameTags => TagRename,
+    RequestDeleteTags => TagDelete,
+}
+
+/// Result of a single action, in the order the action was pushed to the batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActionResult {
+    pub is_success: bool,
+    pub error: Option<String>,
+}
+
+/// A list of mixed actions sent to /v3/send in chunks.
+///
+/// ```ignore
+/// let mut batch = ActionBatch::new();
+/// batch
+///     .push(RequestArchive::new(item_id))
+///     .push(RequestAddTags::new(item_id, &["rust"]))
+///     .push(BulkRequestAdd::new("https://getpocket.com/developer/docs/v3/add"));
+///
+/// let results = get_pocket.send_batch(&batch).await.into_result()?;
+/// ```
+#[derive(Debug)]
+pub struct ActionBatch {
+    actions: Vec<BatchAction>,
+    chunk_size: usize,
+}
+
+impl Default for ActionBatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ActionBatch {
+    pub fn new() -> Self {
+        Self {
+            actions: vec![],
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// Maximum number of actions sent in a single request; at least one.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    pub fn push<A>(&mut self, action: A) -> &mut Self
+    where
+        A: Into<BatchAction>,
+    {
+        self.actions.push(action.into());
+        self
+    }
+
+    pub fn actions(&self) -> &[BatchAction] {
+        &self.actions
+    }
+
+    pub fn chunks(&self) -> std::slice::Chunks<'_, BatchAction> {
+        self.actions.chunks(self.chunk_size)
+    }
+
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+}
+
+impl<A> Extend<A> for ActionBatch
+where
+    A: Into<BatchAction>,
+{
+    fn extend<I: IntoIterator<Item = A>>(&mut self, iter: I) {
+        self.actions.extend(iter.into_iter().map(Into::into));
+    }
+}
+
+/// Outcome of `BatchExt::send_batch`.
+///
+/// Pocket applies every chunk it receives, so the results of the chunks sent
+/// before a failure are kept. The actions from `results.len()` on were not sent.
+#[derive(Debug)]
+pub struct BatchResults {
+    /// Results of the sent actions, aligned with the order they were pushed
+    pub results: Vec<ActionResult>,
+    /// Failure of the first chunk that could not be sent, the batch stops there
+    pub error: Option<Error>,
+}
+
+impl BatchResults {
+    /// Every action was sent
+    pub fn is_complete(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Actions of `batch` that were not sent because of `error`
+    pub fn unsent<'a>(&self, batch: &'a ActionBatch) -> &'a [BatchAction] {
+        batch
+            .actions()
+            .get(self.results.len()..)
+            .unwrap_or_default()
+    }
+
+    /// The results, or the error if a chunk could not be sent
+    pub fn into_result(self) -> Result<Vec<ActionResult>> {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok(self.results),
+        }
+    }
+}
+
+#[async_trait]
+pub trait BatchExt {
+    /// Send every action of the batch, one request per chunk, until a chunk fails.
+    /// The results are aligned with the order the actions were pushed.
+    async fn send_batch(&self, batch: &ActionBatch) -> BatchResults;
+}
+
+#[async_trait]
+impl BatchExt for GetPocket {
+    async fn send_batch(&self, batch: &ActionBatch) -> BatchResults {
+        let mut results = Vec::with_capacity(batch.len());
+
+        for chunk in batch.chunks() {
+            let resp = match self.send(chunk).await {
+                Ok(resp) => resp,
+                Err(err) => {
+                    return BatchResults {
+                        results,
+                        error: Some(err),
+                    }
+                }
+            };
+
+            let action_results = resp.action_results();
+            let action_errors = resp.action_errors();
+
+            results.extend((0..chunk.len()).map(|idx| ActionResult {
+                is_success: action_results.get(idx) == Some(&true),
+                error: action_errors.get(idx).cloned().flatten(),
+            }));
+        }
+
+        BatchResults {
+            results,
+            error: None,
+        }
+    }
+}
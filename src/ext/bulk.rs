@@ -1,36 +1,137 @@
 use crate::{
-    client::GetPocket,
+    client::{GetPocket, RecordSendDirect},
+    error::Result,
     ext::{modifying::*, tags::*},
 };
-use anyhow::Result;
 use async_trait::async_trait;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize)]
 pub struct BulkRecordModified {
     pub action_results: Vec<bool>,
+    #[serde(default)]
+    pub action_errors: Vec<Option<String>>,
     pub status: i32,
 }
 
+impl BulkRecordModified {
+    /// Pairs every action sent with its result, in the order of the input slice.
+    /// Actions without a matching result are reported as failed.
+    pub fn zip<'a, T>(&'a self, params: &'a [T]) -> Vec<(&'a T, bool)> {
+        params
+            .iter()
+            .enumerate()
+            .map(|(idx, param)| (param, self.action_results.get(idx) == Some(&true)))
+            .collect()
+    }
+
+    /// Returns the index and the params of every action that failed,
+    /// together with the error message when Pocket provided one.
+    pub fn failed<'a, T>(&'a self, params: &'a [T]) -> Vec<(usize, &'a T, Option<&'a str>)> {
+        self.zip(params)
+            .into_iter()
+            .enumerate()
+            .filter(|(_, (_, is_success))| !is_success)
+            .map(|(idx, (param, _))| {
+                let error = self
+                    .action_errors
+                    .get(idx)
+                    .and_then(|error| error.as_deref());
+                (idx, param, error)
+            })
+            .collect()
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.action_results.iter().all(|is_success| *is_success)
+    }
+}
+
+impl From<&RecordSendDirect> for BulkRecordModified {
+    fn from(record: &RecordSendDirect) -> Self {
+        Self {
+            action_results: record.action_results(),
+            action_errors: record.action_errors().to_vec(),
+            status: record.status(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
 pub struct BulkRequestAdd {
     pub action: String,
     /// The id of the item to perform the action on.
-    pub item_id: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item_id: Option<i64>,
     /// A Twitter status id; this is used to show tweet attribution.
-    pub ref_id: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ref_id: Option<i64>,
     /// A comma-delimited list of one or more tags.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<String>,
-    /// The time the action occurred. Unix epoch in milliseconds
-    pub time: Option<i32>,
+    /// The time the action occurred. Unix epoch in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<i64>,
     /// The title of the item.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
     /// The url of the item; provide this only if you do not have an item_id.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
 }
 
+impl BulkRequestAdd {
+    /// Add a new item by its url
+    pub fn new(url: &str) -> Self {
+        Self {
+            action: "add".to_string(),
+            item_id: None,
+            ref_id: None,
+            tags: None,
+            time: None,
+            title: None,
+            url: Some(url.to_string()),
+        }
+    }
+
+    /// Add an item that is already known to Pocket by its id
+    pub fn from_item_id(item_id: i64) -> Self {
+        Self {
+            item_id: Some(item_id),
+            url: None,
+            ..Self::new("")
+        }
+    }
+
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    pub fn with_tags(mut self, tags: &[&str]) -> Self {
+        self.tags = Some(tags.join(","));
+        self
+    }
+
+    pub fn with_ref_id(mut self, ref_id: i64) -> Self {
+        self.ref_id = Some(ref_id);
+        self
+    }
+
+    pub fn with_time(mut self, time: i64) -> Self {
+        self.time = Some(time);
+        self
+    }
+}
+
+/// Sends many actions of the same kind in a single request.
+/// <https://getpocket.com/developer/docs/v3/modify>
 #[async_trait]
 pub trait BulkExt {
-    async fn bulk_modify<T>(&self, params: &[T]) -> Result<BulkRecordModified>;
+    /// Send any list of serializable actions in a single request
+    async fn bulk_modify<T>(&self, params: &[T]) -> Result<BulkRecordModified>
+    where
+        T: Serialize + Sync;
 
     async fn bulk_add(&self, params: &[BulkRequestAdd]) -> Result<BulkRecordModified>;
 
@@ -59,58 +160,60 @@ pub trait BulkExt {
 
 #[async_trait]
 impl BulkExt for GetPocket {
-    async fn bulk_modify<T>(&self, _params: &[T]) -> Result<BulkRecordModified> {
-        unimplemented!()
+    async fn bulk_modify<T>(&self, params: &[T]) -> Result<BulkRecordModified>
+    where
+        T: Serialize + Sync,
+    {
+        let resp = &self.send(params).await?;
+
+        Ok(resp.into())
     }
 
-    async fn bulk_add(&self, _params: &[BulkRequestAdd]) -> Result<BulkRecordModified> {
-        unimplemented!()
+    async fn bulk_add(&self, params: &[BulkRequestAdd]) -> Result<BulkRecordModified> {
+        self.bulk_modify(params).await
     }
 
-    async fn bulk_archive(&self, _params: &[RequestArchive]) -> Result<BulkRecordModified> {
-        unimplemented!()
+    async fn bulk_archive(&self, params: &[RequestArchive]) -> Result<BulkRecordModified> {
+        self.bulk_modify(params).await
     }
 
-    async fn bulk_readd(&self, _params: &[RequestReadd]) -> Result<BulkRecordModified> {
-        unimplemented!()
+    async fn bulk_readd(&self, params: &[RequestReadd]) -> Result<BulkRecordModified> {
+        self.bulk_modify(params).await
     }
 
-    async fn bulk_favorite(&self, _params: &[RequestFavorite]) -> Result<BulkRecordModified> {
-        unimplemented!()
+    async fn bulk_favorite(&self, params: &[RequestFavorite]) -> Result<BulkRecordModified> {
+        self.bulk_modify(params).await
     }
 
-    async fn bulk_unfavorite(&self, _params: &[RequestUnfavorite]) -> Result<BulkRecordModified> {
-        unimplemented!()
+    async fn bulk_unfavorite(&self, params: &[RequestUnfavorite]) -> Result<BulkRecordModified> {
+        self.bulk_modify(params).await
     }
 
-    async fn bulk_delete(&self, _params: &[RequestDelete]) -> Result<BulkRecordModified> {
-        unimplemented!()
+    async fn bulk_delete(&self, params: &[RequestDelete]) -> Result<BulkRecordModified> {
+        self.bulk_modify(params).await
     }
 
-    async fn bulk_tags_add(&self, _params: &[RequestAddTags]) -> Result<BulkRecordModified> {
-        unimplemented!()
+    async fn bulk_tags_add(&self, params: &[RequestAddTags]) -> Result<BulkRecordModified> {
+        self.bulk_modify(params).await
     }
 
-    async fn bulk_tags_remove(&self, _params: &[RequestRemoveTags]) -> Result<BulkRecordModified> {
-        unimplemented!()
+    async fn bulk_tags_remove(&self, params: &[RequestRemoveTags]) -> Result<BulkRecordModified> {
+        self.bulk_modify(params).await
     }
 
-    async fn bulk_tags_replace(
-        &self,
-        _params: &[RequestReplaceTags],
-    ) -> Result<BulkRecordModified> {
-        unimplemented!()
+    async fn bulk_tags_replace(&self, params: &[RequestReplaceTags]) -> Result<BulkRecordModified> {
+        self.bulk_modify(params).await
     }
 
-    async fn bulk_tags_clear(&self, _params: &[RequestClearTags]) -> Result<BulkRecordModified> {
-        unimplemented!()
+    async fn bulk_tags_clear(&self, params: &[RequestClearTags]) -> Result<BulkRecordModified> {
+        self.bulk_modify(params).await
     }
 
-    async fn bulk_tag_rename(&self, _params: &[RequestRenameTags]) -> Result<BulkRecordModified> {
-        unimplemented!()
+    async fn bulk_tag_rename(&self, params: &[RequestRenameTags]) -> Result<BulkRecordModified> {
+        self.bulk_modify(params).await
     }
 
-    async fn bulk_tag_delete(&self, _params: &[RequestDeleteTags]) -> Result<BulkRecordModified> {
-        unimplemented!()
+    async fn bulk_tag_delete(&self, params: &[RequestDeleteTags]) -> Result<BulkRecordModified> {
+        self.bulk_modify(params).await
     }
 }
@@ -12,3 +12,9 @@ pub use tags::TagsExt;
 
 pub mod bulk;
 pub use bulk::BulkExt;
+
+pub mod batch;
+pub use batch::BatchExt;
+
+pub mod sync;
+pub use sync::SyncExt;
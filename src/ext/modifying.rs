@@ -3,17 +3,12 @@
 // TODO: remove this derive after implementing the code
 #![allow(dead_code)]
 
-use crate::client::{GetPocket, RecordSendDirect};
-use anyhow::Result;
+use crate::{
+    client::{GetPocket, RecordSendDirect},
+    error::Result,
+};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use thiserror::Error;
-
-#[derive(Error, Debug)]
-pub enum ModifyingError<'a> {
-    #[error("Invalid Params: `{0}`")]
-    InvalidParams(&'a str),
-}
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct RecordModified {
@@ -40,7 +35,7 @@ pub struct RecordModified {
 //                 access_token: access_token,
 //                 actions,
 //             }),
-//             None => bail!(ModifyingError::InvalidParams("No access_token")),
+//             None => return Err(Error::auth("No access token")),
 //         }
 //     }
 // }
@@ -64,9 +59,24 @@ pub struct RequestArchive {
     action: Action,
     /// The id of the item to perform the action on.
     item_id: i64,
-    /// The time the action occurred. Unix epoch in milliseconds
+    /// The time the action occurred. Unix epoch in seconds
     #[serde(skip_serializing_if = "Option::is_none")]
-    time: Option<i32>,
+    time: Option<i64>,
+}
+
+impl RequestArchive {
+    pub fn new(item_id: i64) -> Self {
+        Self {
+            action: Action::Archive,
+            item_id,
+            time: None,
+        }
+    }
+
+    pub fn with_time(mut self, time: i64) -> Self {
+        self.time = Some(time);
+        self
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -74,9 +84,24 @@ pub struct RequestReadd {
     action: Action,
     /// The id of the item to perform the action on.
     item_id: i64,
-    /// The time the action occurred. Unix epoch in milliseconds
+    /// The time the action occurred. Unix epoch in seconds
     #[serde(skip_serializing_if = "Option::is_none")]
-    time: Option<i32>,
+    time: Option<i64>,
+}
+
+impl RequestReadd {
+    pub fn new(item_id: i64) -> Self {
+        Self {
+            action: Action::Readd,
+            item_id,
+            time: None,
+        }
+    }
+
+    pub fn with_time(mut self, time: i64) -> Self {
+        self.time = Some(time);
+        self
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -84,9 +109,24 @@ pub struct RequestFavorite {
     action: Action,
     /// The id of the item to perform the action on.
     item_id: i64,
-    /// The time the action occurred. Unix epoch in milliseconds
+    /// The time the action occurred. Unix epoch in seconds
     #[serde(skip_serializing_if = "Option::is_none")]
-    time: Option<i32>,
+    time: Option<i64>,
+}
+
+impl RequestFavorite {
+    pub fn new(item_id: i64) -> Self {
+        Self {
+            action: Action::Favorite,
+            item_id,
+            time: None,
+        }
+    }
+
+    pub fn with_time(mut self, time: i64) -> Self {
+        self.time = Some(time);
+        self
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -94,9 +134,24 @@ pub struct RequestUnfavorite {
     action: Action,
     /// The id of the item to perform the action on.
     item_id: i64,
-    /// The time the action occurred. Unix epoch in milliseconds
+    /// The time the action occurred. Unix epoch in seconds
     #[serde(skip_serializing_if = "Option::is_none")]
-    time: Option<i32>,
+    time: Option<i64>,
+}
+
+impl RequestUnfavorite {
+    pub fn new(item_id: i64) -> Self {
+        Self {
+            action: Action::Unfavorite,
+            item_id,
+            time: None,
+        }
+    }
+
+    pub fn with_time(mut self, time: i64) -> Self {
+        self.time = Some(time);
+        self
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -104,9 +159,24 @@ pub struct RequestDelete {
     action: Action,
     /// The id of the item to perform the action on.
     item_id: i64,
-    /// The time the action occurred. Unix epoch in milliseconds
+    /// The time the action occurred. Unix epoch in seconds
     #[serde(skip_serializing_if = "Option::is_none")]
-    time: Option<i32>,
+    time: Option<i64>,
+}
+
+impl RequestDelete {
+    pub fn new(item_id: i64) -> Self {
+        Self {
+            action: Action::Delete,
+            item_id,
+            time: None,
+        }
+    }
+
+    pub fn with_time(mut self, time: i64) -> Self {
+        self.time = Some(time);
+        self
+    }
 }
 
 /// <https://getpocket.com/developer/docs/v3/modify>   
@@ -132,11 +202,7 @@ pub trait ModifyingExt {
 impl ModifyingExt for GetPocket {
     /// Move an item to the user's archive
     async fn archive(&self, item_id: i64) -> Result<RecordModified> {
-        let params = RequestArchive {
-            action: Action::Archive,
-            item_id,
-            time: None,
-        };
+        let params = RequestArchive::new(item_id);
 
         let resp = &self.send(&[params]).await?;
 
@@ -145,11 +211,7 @@ impl ModifyingExt for GetPocket {
 
     /// Move an item from the user's archive back into their unread list.
     async fn readd(&self, item_id: i64) -> Result<RecordModified> {
-        let params = RequestReadd {
-            action: Action::Readd,
-            item_id,
-            time: None,
-        };
+        let params = RequestReadd::new(item_id);
 
         let resp = &self.send(&[params]).await?;
 
@@ -158,11 +220,7 @@ impl ModifyingExt for GetPocket {
 
     /// Mark an item as a favorite
     async fn favorite(&self, item_id: i64) -> Result<RecordModified> {
-        let params = RequestFavorite {
-            action: Action::Favorite,
-            item_id,
-            time: None,
-        };
+        let params = RequestFavorite::new(item_id);
 
         let resp = &self.send(&[params]).await?;
 
@@ -171,11 +229,7 @@ impl ModifyingExt for GetPocket {
 
     /// Remove an item from the user's favorites
     async fn unfavorite(&self, item_id: i64) -> Result<RecordModified> {
-        let params = RequestUnfavorite {
-            action: Action::Unfavorite,
-            item_id,
-            time: None,
-        };
+        let params = RequestUnfavorite::new(item_id);
 
         let resp = &self.send(&[params]).await?;
 
@@ -184,11 +238,7 @@ impl ModifyingExt for GetPocket {
 
     /// Permanently remove an item from the user's account
     async fn delete(&self, item_id: i64) -> Result<RecordModified> {
-        let params = RequestDelete {
-            action: Action::Delete,
-            item_id,
-            time: None,
-        };
+        let params = RequestDelete::new(item_id);
 
         let resp = &self.send(&[params]).await?;
 
@@ -200,7 +250,7 @@ impl From<&RecordSendDirect> for RecordModified {
     fn from(record: &RecordSendDirect) -> Self {
         match record {
             RecordSendDirect::Standart(record) => {
-                let Some(first_action_result) = record.action_results.get(0) else {
+                let Some(first_action_result) = record.action_results.first() else {
                     return Self {
                         is_success: false,
                         status: record.status,
@@ -1,20 +1,20 @@
 use crate::{
     client::{GetPocket, *},
-    ApiRequestError,
+    error::{Error, Result},
+    item::{de, Item},
+    request::decode,
+    transport::HttpRequest,
 };
-use anyhow::{bail, format_err, Result};
 use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap as Map;
-use thiserror::Error;
+use std::collections::{BTreeMap as Map, VecDeque};
 
-static ENDPOINT: &'static str = "https://getpocket.com/v3/get";
+static ENDPOINT: &str = "/v3/get";
 
-#[derive(Error, Debug)]
-pub enum RetrievingError<'a> {
-    #[error("Invalid Params: `{0}`")]
-    InvalidParams(&'a str),
-}
+/// Number of items requested per page by `RetrievingExt::list_stream`
+/// when the query has no `count`. Pocket returns at most 30 items per call.
+pub const DEFAULT_PAGE_SIZE: i32 = 30;
 
 #[derive(Debug, Deserialize)]
 pub struct RecordItem {
@@ -22,40 +22,175 @@ pub struct RecordItem {
     #[serde(default)]
     pub complete: Option<i32>,
     pub error: Option<String>,
-    pub since: i32,
-    pub list: Map<String, serde_json::Value>,
+    pub since: i64,
+    /// Total number of items matching the query, returned only when requested with `ListQuery::total`
+    #[serde(default, deserialize_with = "de::opt_int")]
+    pub total: Option<i64>,
+    #[serde(default, deserialize_with = "de::map")]
+    pub list: Map<String, Item>,
 }
 
-#[derive(Serialize)]
-struct RequestParams<'a> {
-    consumer_key: &'a str,
-    access_token: &'a str,
+/// Parameters of a /v3/get request.
+/// <https://getpocket.com/developer/docs/v3/retrieve>
+///
+/// ```ignore
+/// let query = ListQuery::new()
+///     .state(RecordItemState::All)
+///     .tag(RecordItemTag::TagName("rust"))
+///     .detail_type(RecordItemDetailType::Complete)
+///     .count(10);
+///
+/// let resp = get_pocket.list(&query).await?;
+/// ```
+///
+/// Serializes into the parameters sent to Pocket, which is handy for logging.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ListQuery {
     #[serde(skip_serializing_if = "Option::is_none")]
-    state: Option<&'a str>, // ItemState,
+    state: Option<&'static str>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    favorite: Option<i32>, // RecordItemfavorite,
+    favorite: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    tag: Option<&'a str>, // RecordItemTag,
+    tag: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "contentType")]
-    content_type: Option<&'a str>, // RecordItemContentType,
+    content_type: Option<&'static str>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    sort: Option<&'a str>, // RecordItemSort,
+    sort: Option<&'static str>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "detailType")]
-    detail_type: Option<&'a str>, // RecordItemDetailType,
+    detail_type: Option<&'static str>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    search: Option<&'a str>,
+    search: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    domain: Option<&'a str>,
+    domain: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    since: Option<&'a i32>,
-    offset: i32,
-    count: i32,
+    since: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    count: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total: Option<i32>,
+}
+
+impl ListQuery {
+    /// A query with Pocket's defaults: unread items of any kind, without a limit
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn state(mut self, state: RecordItemState) -> Self {
+        self.state = match state {
+            RecordItemState::All => Some("all"),
+            RecordItemState::Unread => None, // default
+            RecordItemState::Archive => Some("archive"),
+        };
+        self
+    }
+
+    pub fn favorite(mut self, favorite: RecordItemFavorite) -> Self {
+        self.favorite = match favorite {
+            RecordItemFavorite::All => None,
+            RecordItemFavorite::Unfavorited => Some(0),
+            RecordItemFavorite::Favorited => Some(1),
+        };
+        self
+    }
+
+    pub fn tag(mut self, tag: RecordItemTag<'_>) -> Self {
+        self.tag = match tag {
+            RecordItemTag::All => None,
+            RecordItemTag::TagName(tag) => Some(tag.to_string()),
+            RecordItemTag::Untagged => Some("_untagged_".to_string()),
+        };
+        self
+    }
+
+    pub fn content_type(mut self, content_type: RecordItemContentType) -> Self {
+        self.content_type = match content_type {
+            RecordItemContentType::All => None,
+            RecordItemContentType::Article => Some("article"),
+            RecordItemContentType::Video => Some("video"),
+            RecordItemContentType::Image => Some("image"),
+        };
+        self
+    }
+
+    pub fn sort(mut self, sort: RecordItemSort) -> Self {
+        self.sort = match sort {
+            RecordItemSort::All => None,
+            RecordItemSort::Newest => Some("newest"),
+            RecordItemSort::Oldest => Some("oldest"),
+            RecordItemSort::Title => Some("title"),
+            RecordItemSort::Site => Some("site"),
+        };
+        self
+    }
+
+    pub fn detail_type(mut self, detail_type: RecordItemDetailType) -> Self {
+        self.detail_type = match detail_type {
+            RecordItemDetailType::All => None,
+            RecordItemDetailType::Simple => Some("simple"),
+            RecordItemDetailType::Complete => Some("complete"),
+        };
+        self
+    }
+
+    /// Only return items whose title or url contain the search string
+    pub fn search(mut self, search: &str) -> Self {
+        self.search = Some(search)
+            .filter(|search| !search.is_empty())
+            .map(Into::into);
+        self
+    }
+
+    /// Only return items from a particular domain
+    pub fn domain(mut self, domain: &str) -> Self {
+        self.domain = Some(domain)
+            .filter(|domain| !domain.is_empty())
+            .map(Into::into);
+        self
+    }
+
+    /// Only return items modified since the given Unix epoch in seconds
+    pub fn since(mut self, since: i64) -> Self {
+        self.since = Some(since).filter(|since| *since >= 0);
+        self
+    }
+
+    pub fn offset(mut self, offset: i32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    pub fn count(mut self, count: i32) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    /// Ask Pocket to return the total number of matching items in `RecordItem::total`
+    pub fn total(mut self, total: bool) -> Self {
+        self.total = Some(1).filter(|_| total);
+        self
+    }
+}
+
+#[derive(Serialize)]
+struct RequestParams<'a> {
+    consumer_key: &'a str,
+    access_token: &'a str,
+    #[serde(flatten)]
+    query: &'a ListQuery,
 }
 
+/// <https://getpocket.com/developer/docs/v3/retrieve>
 #[async_trait]
 pub trait RetrievingExt {
+    /// Retrieve the items matching the query
+    async fn list(&self, query: &ListQuery) -> Result<RecordItem>;
+
+    #[allow(clippy::too_many_arguments)]
     async fn list_of_items_with_params<'a>(
         &self,
         state: RecordItemState,
@@ -74,10 +209,43 @@ pub trait RetrievingExt {
     async fn list_of_items_paginate(&self, offset: i32, count: i32) -> Result<RecordItem>;
 
     async fn list_of_items(&self) -> Result<RecordItem>;
+
+    /// Lazily page through every item matching the query.
+    ///
+    /// The query `count` is used as the page size (`DEFAULT_PAGE_SIZE` if not set)
+    /// and its `offset` as the first item to return. Pocket may return fewer items
+    /// than requested, so the stream only ends on an empty page, once the offset
+    /// reaches the `total` reported by Pocket, or right after the first error.
+    ///
+    /// ```ignore
+    /// let query = ListQuery::new().state(RecordItemState::All).count(500);
+    /// let mut items = get_pocket.list_stream(query).take(1000);
+    ///
+    /// while let Some(item) = items.next().await {
+    ///     println!("{:?}", item?.url());
+    /// }
+    /// ```
+    fn list_stream(&self, query: ListQuery) -> BoxStream<'_, Result<Item>>;
 }
 
 #[async_trait]
 impl RetrievingExt for GetPocket {
+    async fn list(&self, query: &ListQuery) -> Result<RecordItem> {
+        let params = match &self.token.access_token {
+            Some(access_token) => RequestParams {
+                consumer_key: self.consumer_key.expose(),
+                access_token: access_token.expose(),
+                query,
+            },
+            None => return Err(Error::auth("No access token")),
+        };
+
+        let request = HttpRequest::post(self.endpoint(ENDPOINT)).json(&params)?;
+        let res = self.execute(request, true).await?;
+
+        decode(res.text())
+    }
+
     async fn list_of_items_with_params<'a>(
         &self,
         state: RecordItemState,
@@ -92,93 +260,91 @@ impl RetrievingExt for GetPocket {
         offset: i32,
         count: i32,
     ) -> Result<RecordItem> {
-        let params = match &self.token.access_token {
-            Some(access_token) => RequestParams {
-                consumer_key: &self.consumer_key,
-                access_token,
-                state: match state {
-                    RecordItemState::All => Some("all"),
-                    RecordItemState::Unread => None, // default
-                    RecordItemState::Archive => Some("archive"),
-                },
-                favorite: match favorite {
-                    RecordItemFavorite::All => None,
-                    RecordItemFavorite::Unfavorited => Some(0),
-                    RecordItemFavorite::Favorited => Some(1),
-                },
-                tag: match tag {
-                    RecordItemTag::All => None,
-                    RecordItemTag::TagName(tag) => Some(tag),
-                    RecordItemTag::Untagged => Some("_untagged_"),
-                },
-                content_type: match content_type {
-                    RecordItemContentType::All => None,
-                    RecordItemContentType::Article => Some("article"),
-                    RecordItemContentType::Video => Some("video"),
-                    RecordItemContentType::Image => Some("image"),
-                },
-                sort: match sort {
-                    RecordItemSort::All => None,
-                    RecordItemSort::Newest => Some("newest"),
-                    RecordItemSort::Oldest => Some("oldest"),
-                    RecordItemSort::Title => Some("title"),
-                    RecordItemSort::Site => Some("site"),
-                },
-                detail_type: match detail_type {
-                    RecordItemDetailType::All => None,
-                    RecordItemDetailType::Simple => Some("simple"),
-                    RecordItemDetailType::Complete => Some("complete"),
-                },
-                search: match search {
-                    Some(search) if !search.is_empty() => Some(search),
-                    _ => None,
-                },
-                domain: match domain {
-                    Some(domain) if !domain.is_empty() => Some(domain),
-                    _ => None,
-                },
-                since: match since {
-                    Some(since) if *since >= 0 => Some(since),
-                    _ => None,
-                },
-                offset,
-                count,
-            },
-            None => bail!(RetrievingError::InvalidParams("No access_token")),
-        };
-
-        let client = &self.reqwester.client;
-        let res = client.post(ENDPOINT).json(&params).send().await?;
+        let mut query = ListQuery::new()
+            .state(state)
+            .favorite(favorite)
+            .tag(tag)
+            .content_type(content_type)
+            .sort(sort)
+            .detail_type(detail_type)
+            .offset(offset)
+            .count(count);
 
-        if let Err(err) = ApiRequestError::handler_status(res.status()) {
-            bail!(err);
+        if let Some(search) = search {
+            query = query.search(search);
+        }
+        if let Some(domain) = domain {
+            query = query.domain(domain);
+        }
+        if let Some(since) = since {
+            query = query.since(*since as i64);
         }
 
-        let res_body = &res.text().await?;
-
-        let res_ser: RecordItem = serde_json::from_str(&res_body).map_err(|e| format_err!(e))?;
-
-        Ok(res_ser)
+        self.list(&query).await
     }
 
     async fn list_of_items_paginate(&self, offset: i32, count: i32) -> Result<RecordItem> {
-        self.list_of_items_with_params(
-            RecordItemState::default(),
-            RecordItemFavorite::default(),
-            RecordItemTag::default(),
-            RecordItemContentType::default(),
-            RecordItemSort::default(),
-            RecordItemDetailType::default(),
-            None,
-            None,
-            None,
-            offset,
-            count,
-        )
-        .await
+        self.list(&ListQuery::new().offset(offset).count(count))
+            .await
     }
 
     async fn list_of_items(&self) -> Result<RecordItem> {
         self.list_of_items_paginate(0, 25).await
     }
+
+    fn list_stream(&self, query: ListQuery) -> BoxStream<'_, Result<Item>> {
+        struct Pages<'a> {
+            get_pocket: &'a GetPocket,
+            query: ListQuery,
+            page_size: i32,
+            offset: i32,
+            buffer: VecDeque<Item>,
+            is_last_page: bool,
+        }
+
+        let pages = Pages {
+            get_pocket: self,
+            page_size: query
+                .count
+                .filter(|count| *count > 0)
+                .unwrap_or(DEFAULT_PAGE_SIZE),
+            offset: query.offset.unwrap_or_default(),
+            query,
+            buffer: VecDeque::new(),
+            is_last_page: false,
+        };
+
+        stream::try_unfold(pages, |mut pages| async move {
+            loop {
+                if let Some(item) = pages.buffer.pop_front() {
+                    return Ok(Some((item, pages)));
+                }
+
+                if pages.is_last_page {
+                    return Ok(None);
+                }
+
+                let query = pages
+                    .query
+                    .clone()
+                    .offset(pages.offset)
+                    .count(pages.page_size)
+                    .total(true);
+                let page = pages.get_pocket.list(&query).await?;
+
+                let mut items: Vec<Item> = page.list.into_values().collect();
+                // The list is keyed by item id, `sort_id` keeps the requested order
+                items.sort_by_key(|item| item.sort_id.unwrap_or(i64::MAX));
+
+                pages.offset += items.len() as i32;
+                // A short page is not the last one, Pocket caps the count per call
+                pages.is_last_page = items.is_empty()
+                    || page
+                        .total
+                        .map_or(false, |total| pages.offset as i64 >= total);
+                pages.buffer.extend(items);
+            }
+        })
+        .boxed()
+    }
 }
@@ -1,19 +1,9 @@
-// TODO: remove this derive after implementing the code
-#![allow(unused_variables)]
-// TODO: remove this derive after implementing the code
-#![allow(dead_code)]
-
-use crate::client::GetPocket;
-use anyhow::Result;
+use crate::{
+    client::{GetPocket, RecordSendDirect},
+    error::Result,
+};
 use async_trait::async_trait;
-use serde::Deserialize;
-use thiserror::Error;
-
-#[derive(Error, Debug)]
-pub enum TagsError<'a> {
-    #[error("Invalid Params: `{0}`")]
-    InvalidParams(&'a str),
-}
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize)]
 pub struct RecordModified {
@@ -21,106 +11,256 @@ pub struct RecordModified {
     pub status: i32,
 }
 
-struct RequestParams<T> {
-    consumer_key: String,
-    access_token: String,
-    actions: T,
+#[derive(Debug, Serialize)]
+pub enum Action {
+    #[serde(rename = "tags_add")]
+    TagsAdd,
+    #[serde(rename = "tags_remove")]
+    TagsRemove,
+    #[serde(rename = "tags_replace")]
+    TagsReplace,
+    #[serde(rename = "tags_clear")]
+    TagsClear,
+    #[serde(rename = "tag_rename")]
+    TagRename,
+    #[serde(rename = "tag_delete")]
+    TagDelete,
 }
 
+#[derive(Debug, Serialize)]
 pub struct RequestAddTags {
+    action: Action,
     /// The id of the item to perform the action on.
-    item_id: i32,
+    item_id: i64,
     /// A comma-delimited list of one or more tags.
     tags: String,
-    /// The time the action occurred. Unix epoch in milliseconds
-    time: Option<i32>,
+    /// The time the action occurred. Unix epoch in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time: Option<i64>,
+}
+
+impl RequestAddTags {
+    pub fn new(item_id: i64, tags: &[&str]) -> Self {
+        Self {
+            action: Action::TagsAdd,
+            item_id,
+            tags: tags.join(","),
+            time: None,
+        }
+    }
+
+    pub fn with_time(mut self, time: i64) -> Self {
+        self.time = Some(time);
+        self
+    }
 }
 
+#[derive(Debug, Serialize)]
 pub struct RequestRemoveTags {
+    action: Action,
     /// The id of the item to perform the action on.
-    item_id: i32,
+    item_id: i64,
     /// A comma-delimited list of one or more tags.
     tags: String,
-    /// The time the action occurred. Unix epoch in milliseconds
-    time: Option<i32>,
+    /// The time the action occurred. Unix epoch in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time: Option<i64>,
+}
+
+impl RequestRemoveTags {
+    pub fn new(item_id: i64, tags: &[&str]) -> Self {
+        Self {
+            action: Action::TagsRemove,
+            item_id,
+            tags: tags.join(","),
+            time: None,
+        }
+    }
+
+    pub fn with_time(mut self, time: i64) -> Self {
+        self.time = Some(time);
+        self
+    }
 }
 
+#[derive(Debug, Serialize)]
 pub struct RequestReplaceTags {
+    action: Action,
     /// The id of the item to perform the action on.
-    item_id: i32,
+    item_id: i64,
     /// A comma-delimited list of one or more tags.
     tags: String,
-    /// The time the action occurred. Unix epoch in milliseconds
-    time: Option<i32>,
+    /// The time the action occurred. Unix epoch in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time: Option<i64>,
+}
+
+impl RequestReplaceTags {
+    pub fn new(item_id: i64, tags: &[&str]) -> Self {
+        Self {
+            action: Action::TagsReplace,
+            item_id,
+            tags: tags.join(","),
+            time: None,
+        }
+    }
+
+    pub fn with_time(mut self, time: i64) -> Self {
+        self.time = Some(time);
+        self
+    }
 }
 
+#[derive(Debug, Serialize)]
 pub struct RequestClearTags {
+    action: Action,
     /// The id of the item to perform the action on.
-    item_id: i32,
-    /// The time the action occurred. Unix epoch in milliseconds
-    time: Option<i32>,
+    item_id: i64,
+    /// The time the action occurred. Unix epoch in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time: Option<i64>,
 }
 
+impl RequestClearTags {
+    pub fn new(item_id: i64) -> Self {
+        Self {
+            action: Action::TagsClear,
+            item_id,
+            time: None,
+        }
+    }
+
+    pub fn with_time(mut self, time: i64) -> Self {
+        self.time = Some(time);
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
 pub struct RequestRenameTags {
+    action: Action,
     /// The tag name that will be replaced.
     old_tag: String,
     /// The new tag name that will be added.
     new_tag: String,
-    /// The time the action occurred. Unix epoch in milliseconds
-    time: Option<i32>,
+    /// The time the action occurred. Unix epoch in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time: Option<i64>,
+}
+
+impl RequestRenameTags {
+    pub fn new(old_tag: &str, new_tag: &str) -> Self {
+        Self {
+            action: Action::TagRename,
+            old_tag: old_tag.to_string(),
+            new_tag: new_tag.to_string(),
+            time: None,
+        }
+    }
+
+    pub fn with_time(mut self, time: i64) -> Self {
+        self.time = Some(time);
+        self
+    }
 }
 
+#[derive(Debug, Serialize)]
 pub struct RequestDeleteTags {
+    action: Action,
     /// The tag name that will be deleted.
     tag: String,
-    /// The time the action occurred. Unix epoch in milliseconds
-    time: Option<i32>,
+    /// The time the action occurred. Unix epoch in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time: Option<i64>,
+}
+
+impl RequestDeleteTags {
+    pub fn new(tag: &str) -> Self {
+        Self {
+            action: Action::TagDelete,
+            tag: tag.to_string(),
+            time: None,
+        }
+    }
+
+    pub fn with_time(mut self, time: i64) -> Self {
+        self.time = Some(time);
+        self
+    }
 }
 
+/// <https://getpocket.com/developer/docs/v3/modify>
 #[async_trait]
 pub trait TagsExt {
+    /// Add one or more tags to an item
     async fn tags_add(&self, params: &RequestAddTags) -> Result<RecordModified>;
 
+    /// Remove one or more tags from an item
     async fn tags_remove(&self, params: &RequestRemoveTags) -> Result<RecordModified>;
 
+    /// Replace all of the tags for an item with one or more provided tags
     async fn tags_replace(&self, params: &RequestReplaceTags) -> Result<RecordModified>;
 
+    /// Remove all tags from an item
     async fn tags_clear(&self, params: &RequestClearTags) -> Result<RecordModified>;
 
+    /// Rename a tag; this affects all items with this tag
     async fn tag_rename(&self, params: &RequestRenameTags) -> Result<RecordModified>;
 
+    /// Delete a tag; this affects all items with this tag
     async fn tag_delete(&self, params: &RequestDeleteTags) -> Result<RecordModified>;
 }
 
 #[async_trait]
 impl TagsExt for GetPocket {
     /// Add one or more tags to an item
-    async fn tags_add(&self, _params: &RequestAddTags) -> Result<RecordModified> {
-        unimplemented!()
+    async fn tags_add(&self, params: &RequestAddTags) -> Result<RecordModified> {
+        let resp = &self.send(&[params]).await?;
+
+        Ok(resp.into())
     }
 
     /// Remove one or more tags from an item
-    async fn tags_remove(&self, _params: &RequestRemoveTags) -> Result<RecordModified> {
-        unimplemented!()
+    async fn tags_remove(&self, params: &RequestRemoveTags) -> Result<RecordModified> {
+        let resp = &self.send(&[params]).await?;
+
+        Ok(resp.into())
     }
 
     /// Replace all of the tags for an item with one or more provided tags
-    async fn tags_replace(&self, _params: &RequestReplaceTags) -> Result<RecordModified> {
-        unimplemented!()
+    async fn tags_replace(&self, params: &RequestReplaceTags) -> Result<RecordModified> {
+        let resp = &self.send(&[params]).await?;
+
+        Ok(resp.into())
     }
 
     /// Remove all tags from an item
-    async fn tags_clear(&self, _params: &RequestClearTags) -> Result<RecordModified> {
-        unimplemented!()
+    async fn tags_clear(&self, params: &RequestClearTags) -> Result<RecordModified> {
+        let resp = &self.send(&[params]).await?;
+
+        Ok(resp.into())
     }
 
     /// Rename a tag; this affects all items with this tag
-    async fn tag_rename(&self, _params: &RequestRenameTags) -> Result<RecordModified> {
-        unimplemented!()
+    async fn tag_rename(&self, params: &RequestRenameTags) -> Result<RecordModified> {
+        let resp = &self.send(&[params]).await?;
+
+        Ok(resp.into())
     }
 
     /// Delete a tag; this affects all items with this tag
-    async fn tag_delete(&self, _params: &RequestDeleteTags) -> Result<RecordModified> {
-        unimplemented!()
+    async fn tag_delete(&self, params: &RequestDeleteTags) -> Result<RecordModified> {
+        let resp = &self.send(&[params]).await?;
+
+        Ok(resp.into())
+    }
+}
+
+impl From<&RecordSendDirect> for RecordModified {
+    fn from(record: &RecordSendDirect) -> Self {
+        Self {
+            action_results: record.action_results(),
+            status: record.status(),
+        }
     }
 }
@@ -0,0 +1,282 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap as Map;
+
+/// An item saved in the user's list.
+///
+/// Pocket encodes most numbers and flags as strings (`"favorite": "0"`),
+/// every field here accepts both the string and the native representation.
+/// Fields that are missing in the response are left at their default value,
+/// e.g. items removed from the list only carry `item_id` and `status`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Item {
+    #[serde(deserialize_with = "de::int")]
+    pub item_id: i64,
+    #[serde(deserialize_with = "de::opt_int")]
+    pub resolved_id: Option<i64>,
+    pub given_url: Option<String>,
+    pub given_title: Option<String>,
+    pub resolved_url: Option<String>,
+    pub resolved_title: Option<String>,
+    /// Returned by /v3/add instead of `resolved_url`
+    pub normal_url: Option<String>,
+    /// Returned by /v3/add instead of `resolved_title`
+    pub title: Option<String>,
+    pub excerpt: Option<String>,
+    pub status: ItemStatus,
+    #[serde(deserialize_with = "de::bool")]
+    pub favorite: bool,
+    #[serde(deserialize_with = "de::bool")]
+    pub is_article: bool,
+    #[serde(deserialize_with = "de::bool")]
+    pub is_index: bool,
+    /// `true` if the item has images in it or is an image
+    #[serde(deserialize_with = "de::bool")]
+    pub has_image: bool,
+    /// `true` if the item has videos in it or is a video
+    #[serde(deserialize_with = "de::bool")]
+    pub has_video: bool,
+    #[serde(deserialize_with = "de::opt_int")]
+    pub word_count: Option<i64>,
+    /// Estimated reading time in minutes
+    #[serde(deserialize_with = "de::opt_int")]
+    pub time_to_read: Option<i64>,
+    /// Estimated listening time in seconds
+    #[serde(deserialize_with = "de::opt_int")]
+    pub listen_duration_estimate: Option<i64>,
+    pub lang: Option<String>,
+    pub top_image_url: Option<String>,
+    /// Unix epoch in seconds
+    #[serde(deserialize_with = "de::opt_timestamp")]
+    pub time_added: Option<i64>,
+    /// Unix epoch in seconds
+    #[serde(deserialize_with = "de::opt_timestamp")]
+    pub time_updated: Option<i64>,
+    /// Unix epoch in seconds
+    #[serde(deserialize_with = "de::opt_timestamp")]
+    pub time_read: Option<i64>,
+    /// Unix epoch in seconds
+    #[serde(deserialize_with = "de::opt_timestamp")]
+    pub time_favorited: Option<i64>,
+    #[serde(deserialize_with = "de::opt_int")]
+    pub sort_id: Option<i64>,
+    #[serde(deserialize_with = "de::map")]
+    pub tags: Map<String, ItemTag>,
+    #[serde(deserialize_with = "de::map")]
+    pub authors: Map<String, ItemAuthor>,
+    #[serde(deserialize_with = "de::map")]
+    pub images: Map<String, ItemImage>,
+    #[serde(deserialize_with = "de::map")]
+    pub videos: Map<String, ItemVideo>,
+    /// The top image of the item
+    pub image: Option<ItemImage>,
+    pub domain_metadata: Option<DomainMetadata>,
+}
+
+impl Item {
+    /// The best known url of the item
+    pub fn url(&self) -> Option<&str> {
+        [&self.resolved_url, &self.given_url, &self.normal_url]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .find(|url| !url.is_empty())
+    }
+
+    /// The best known title of the item
+    pub fn display_title(&self) -> Option<&str> {
+        [&self.resolved_title, &self.given_title, &self.title]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .find(|title| !title.is_empty())
+    }
+
+    pub fn tag_names(&self) -> impl Iterator<Item = &str> {
+        self.tags.keys().map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ItemStatus {
+    #[default]
+    Unread,
+    Archived,
+    /// The item should be deleted
+    Deleted,
+}
+
+impl ItemStatus {
+    pub fn as_i64(&self) -> i64 {
+        match self {
+            ItemStatus::Unread => 0,
+            ItemStatus::Archived => 1,
+            ItemStatus::Deleted => 2,
+        }
+    }
+}
+
+impl TryFrom<i64> for ItemStatus {
+    type Error = String;
+
+    fn try_from(status: i64) -> Result<Self, Self::Error> {
+        match status {
+            0 => Ok(ItemStatus::Unread),
+            1 => Ok(ItemStatus::Archived),
+            2 => Ok(ItemStatus::Deleted),
+            status => Err(format!("unknown item status `{status}`")),
+        }
+    }
+}
+
+impl Serialize for ItemStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.as_i64())
+    }
+}
+
+impl<'de> Deserialize<'de> for ItemStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let status = de::int(deserializer)?;
+
+        ItemStatus::try_from(status).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ItemTag {
+    #[serde(deserialize_with = "de::opt_int")]
+    pub item_id: Option<i64>,
+    pub tag: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ItemAuthor {
+    #[serde(deserialize_with = "de::opt_int")]
+    pub item_id: Option<i64>,
+    #[serde(deserialize_with = "de::opt_int")]
+    pub author_id: Option<i64>,
+    pub name: String,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ItemImage {
+    #[serde(deserialize_with = "de::opt_int")]
+    pub item_id: Option<i64>,
+    #[serde(deserialize_with = "de::opt_int")]
+    pub image_id: Option<i64>,
+    pub src: String,
+    #[serde(deserialize_with = "de::opt_int")]
+    pub width: Option<i64>,
+    #[serde(deserialize_with = "de::opt_int")]
+    pub height: Option<i64>,
+    pub credit: Option<String>,
+    pub caption: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ItemVideo {
+    #[serde(deserialize_with = "de::opt_int")]
+    pub item_id: Option<i64>,
+    #[serde(deserialize_with = "de::opt_int")]
+    pub video_id: Option<i64>,
+    pub src: String,
+    #[serde(deserialize_with = "de::opt_int")]
+    pub width: Option<i64>,
+    #[serde(deserialize_with = "de::opt_int")]
+    pub height: Option<i64>,
+    /// The id of the video on its hosting service (e.g. YouTube)
+    pub vid: Option<String>,
+    #[serde(rename = "type", deserialize_with = "de::opt_int")]
+    pub video_type: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DomainMetadata {
+    pub name: Option<String>,
+    pub logo: Option<String>,
+    pub greyscale_logo: Option<String>,
+}
+
+/// Lenient deserializers for the string-encoded values returned by Pocket.
+pub(crate) mod de {
+    use serde::{de::Error, Deserialize, Deserializer};
+    use std::collections::BTreeMap as Map;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Lenient {
+        Bool(bool),
+        Int(i64),
+        Float(f64),
+        Str(String),
+    }
+
+    impl Lenient {
+        fn into_int<E: Error>(self) -> Result<Option<i64>, E> {
+            match self {
+                Lenient::Bool(value) => Ok(Some(value as i64)),
+                Lenient::Int(value) => Ok(Some(value)),
+                Lenient::Float(value) => Ok(Some(value as i64)),
+                Lenient::Str(value) if value.trim().is_empty() => Ok(None),
+                Lenient::Str(value) => value
+                    .trim()
+                    .parse::<i64>()
+                    .map(Some)
+                    .map_err(|_| E::custom(format!("invalid number `{value}`"))),
+            }
+        }
+    }
+
+    pub fn int<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+        Ok(opt_int(deserializer)?.unwrap_or_default())
+    }
+
+    pub fn opt_int<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+        match Option::<Lenient>::deserialize(deserializer)? {
+            Some(value) => value.into_int(),
+            None => Ok(None),
+        }
+    }
+
+    /// Pocket uses `"0"` for timestamps that are not set.
+    pub fn opt_timestamp<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<i64>, D::Error> {
+        Ok(opt_int(deserializer)?.filter(|timestamp| *timestamp > 0))
+    }
+
+    pub fn bool<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+        Ok(opt_int(deserializer)?.unwrap_or_default() != 0)
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum MapOrSeq<T> {
+        Map(Map<String, T>),
+        Seq(Vec<T>),
+    }
+
+    /// Pocket returns an empty array instead of an empty object.
+    pub fn map<'de, D, T>(deserializer: D) -> Result<Map<String, T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        match Option::<MapOrSeq<T>>::deserialize(deserializer)? {
+            Some(MapOrSeq::Map(map)) => Ok(map),
+            Some(MapOrSeq::Seq(seq)) => Ok(seq
+                .into_iter()
+                .enumerate()
+                .map(|(idx, value)| (idx.to_string(), value))
+                .collect()),
+            None => Ok(Map::new()),
+        }
+    }
+}
@@ -1,13 +1,52 @@
+pub mod auth;
+
+mod builder;
+pub use builder::GetPocketBuilder;
+
 mod client;
-pub use client::GetPocket;
+pub use client::{GetPocket, DEFAULT_BASE_URL};
+
+mod config;
+pub use config::{Config, RetryConfig};
 
 mod ext;
 pub use ext::adding;
+pub use ext::batch;
+pub use ext::bulk;
 pub use ext::modifying;
 pub use ext::retrieving;
+pub use ext::sync;
+pub use ext::tags;
+pub use ext::{AddingExt, BatchExt, BulkExt, ModifyingExt, RetrievingExt, SyncExt, TagsExt};
+
+mod item;
+pub use item::{DomainMetadata, Item, ItemAuthor, ItemImage, ItemStatus, ItemTag, ItemVideo};
+
+#[cfg(feature = "cache")]
+pub mod cache;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+mod rate_limit;
+pub use rate_limit::{RateLimitInfo, Throttle};
+
+mod retry;
+pub use retry::RetryPolicy;
+
+mod error;
+pub use error::{Error, Result};
 
 mod request;
-pub use request::ApiRequestError;
+
+mod secret;
+pub use secret::Secret;
+
+mod token_store;
+pub use token_store::{FileTokenStore, MemoryTokenStore, TokenStore};
+
+mod transport;
+pub use transport::{HttpRequest, HttpResponse, HttpTransport, MemoryTransport, ReqwestTransport};
 
 pub use client::{
     RecordItemContentType, RecordItemDetailType, RecordItemFavorite, RecordItemSort,
@@ -0,0 +1,169 @@
+use reqwest::header::HeaderMap;
+use std::time::{Duration, Instant};
+
+/// Current rate limit enforced per user
+pub const X_LIMIT_USER_LIMIT: &str = "X-Limit-User-Limit";
+/// Number of calls remaining before hitting user's rate limit
+pub const X_LIMIT_USER_REMAINING: &str = "X-Limit-User-Remaining";
+/// Seconds until user's rate limit resets
+pub const X_LIMIT_USER_RESET: &str = "X-Limit-User-Reset";
+/// Current rate limit enforced per consumer key
+pub const X_LIMIT_KEY_LIMIT: &str = "X-Limit-Key-Limit";
+/// Number of calls remaining before hitting consumer key's rate limit
+pub const X_LIMIT_KEY_REMAINING: &str = "X-Limit-Key-Remaining";
+/// Seconds until consumer key rate limit resets
+pub const X_LIMIT_KEY_RESET: &str = "X-Limit-Key-Reset";
+
+/// Rate limits reported by Pocket in the `X-Limit-*` headers of a response.
+/// <https://getpocket.com/developer/docs/rate-limits>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitInfo {
+    pub user_limit: Option<u32>,
+    pub user_remaining: Option<u32>,
+    /// Seconds until user's rate limit resets, counted from `received_at`
+    pub user_reset: Option<u64>,
+    pub key_limit: Option<u32>,
+    pub key_remaining: Option<u32>,
+    /// Seconds until consumer key rate limit resets, counted from `received_at`
+    pub key_reset: Option<u64>,
+    pub received_at: Instant,
+}
+
+impl RateLimitInfo {
+    /// Returns `None` if the response carries none of the `X-Limit-*` headers
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        fn header<T: std::str::FromStr>(headers: &HeaderMap, name: &str) -> Option<T> {
+            headers.get(name)?.to_str().ok()?.trim().parse().ok()
+        }
+
+        let info = Self {
+            user_limit: header(headers, X_LIMIT_USER_LIMIT),
+            user_remaining: header(headers, X_LIMIT_USER_REMAINING),
+            user_reset: header(headers, X_LIMIT_USER_RESET),
+            key_limit: header(headers, X_LIMIT_KEY_LIMIT),
+            key_remaining: header(headers, X_LIMIT_KEY_REMAINING),
+            key_reset: header(headers, X_LIMIT_KEY_RESET),
+            received_at: Instant::now(),
+        };
+
+        let is_empty = info.user_limit.is_none()
+            && info.user_remaining.is_none()
+            && info.user_reset.is_none()
+            && info.key_limit.is_none()
+            && info.key_remaining.is_none()
+            && info.key_reset.is_none();
+
+        (!is_empty).then_some(info)
+    }
+
+    pub fn user_reset_at(&self) -> Option<Instant> {
+        self.user_reset
+            .map(|reset| self.received_at + Duration::from_secs(reset))
+    }
+
+    pub fn key_reset_at(&self) -> Option<Instant> {
+        self.key_reset
+            .map(|reset| self.received_at + Duration::from_secs(reset))
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.user_remaining == Some(0) || self.key_remaining == Some(0)
+    }
+
+    /// `Some` if no call remains, with the seconds until the exhausted limit resets
+    pub(crate) fn exhausted_reset(&self) -> Option<Option<u64>> {
+        match (self.user_remaining, self.key_remaining) {
+            (Some(0), Some(0)) => Some(self.user_reset.max(self.key_reset)),
+            (Some(0), _) => Some(self.user_reset),
+            (_, Some(0)) => Some(self.key_reset),
+            _ => None,
+        }
+    }
+}
+
+/// Opt-in governor that paces calls using the quota reported by Pocket.
+///
+/// Once the remaining calls of the user or the consumer key drop to `reserve`,
+/// the next calls wait for the limit to reset instead of being rejected.
+/// A call rejected with 403 while the quota is exhausted is retried after the reset,
+/// or after a backoff if Pocket did not report when it resets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Throttle {
+    /// Number of calls kept in reserve before waiting for the reset
+    pub reserve: u32,
+    /// Longest time a call may wait for the reset, it fails beyond that
+    pub max_wait: Duration,
+}
+
+impl Default for Throttle {
+    fn default() -> Self {
+        Self {
+            reserve: 0,
+            max_wait: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+impl Throttle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_reserve(mut self, reserve: u32) -> Self {
+        self.reserve = reserve;
+        self
+    }
+
+    pub fn with_max_wait(mut self, max_wait: Duration) -> Self {
+        self.max_wait = max_wait;
+        self
+    }
+
+    /// Time to wait before the next call, or `None` if a call is available.
+    /// An available call is taken from the known quota, so that concurrent
+    /// calls do not all spend the last one.
+    pub(crate) fn acquire(&self, rate_limit: &mut Option<RateLimitInfo>) -> Option<Duration> {
+        let rate_limit = rate_limit.as_mut()?;
+        let now = Instant::now();
+
+        let user_wait = Self::wait(
+            self.reserve,
+            rate_limit.user_remaining,
+            rate_limit.user_reset_at(),
+            now,
+        );
+        let key_wait = Self::wait(
+            self.reserve,
+            rate_limit.key_remaining,
+            rate_limit.key_reset_at(),
+            now,
+        );
+
+        match user_wait.max(key_wait) {
+            Some(wait) => Some(wait),
+            None => {
+                if let Some(remaining) = rate_limit.user_remaining.as_mut() {
+                    *remaining = remaining.saturating_sub(1);
+                }
+                if let Some(remaining) = rate_limit.key_remaining.as_mut() {
+                    *remaining = remaining.saturating_sub(1);
+                }
+                None
+            }
+        }
+    }
+
+    fn wait(
+        reserve: u32,
+        remaining: Option<u32>,
+        reset_at: Option<Instant>,
+        now: Instant,
+    ) -> Option<Duration> {
+        match (remaining, reset_at) {
+            (Some(remaining), Some(reset_at)) if remaining <= reserve && reset_at > now => {
+                Some(reset_at - now)
+            }
+            _ => None,
+        }
+    }
+}
@@ -1,19 +1,88 @@
-use anyhow::{bail, Result};
-use reqwest::StatusCode;
-use thiserror::Error;
-
-#[derive(Error, Debug)]
-#[error("Request has encountered an error. {0} - {1} ")]
-pub struct ApiRequestError<'a>(u32, &'a str);
-
-impl ApiRequestError<'_> {
-    pub fn handler_status(status_code: StatusCode) -> Result<()> {
-        match status_code {
-            StatusCode::BAD_REQUEST => bail!(ApiRequestError(400, "Invalid request, please make sure you follow the documentation for proper syntax.")),
-            StatusCode::UNAUTHORIZED => bail!(ApiRequestError(401, "Problem authenticating the user.")),
-            StatusCode::FORBIDDEN => bail!(ApiRequestError(403, "User was authenticated, but access denied due to lack of permission or rate limiting.")),
-            StatusCode::SERVICE_UNAVAILABLE => bail!(ApiRequestError(502, "Pocket's sync server is down for scheduled maintenance.")),
-            _ => Ok(()),
+use crate::{
+    error::{Error, Result},
+    rate_limit::RateLimitInfo,
+};
+use reqwest::{header::HeaderMap, StatusCode};
+use serde::de::DeserializeOwned;
+
+/// Reason of the failure returned by Pocket
+pub const X_ERROR: &str = "X-Error";
+/// Pocket's error code, see <https://getpocket.com/developer/docs/errors>
+pub const X_ERROR_CODE: &str = "X-Error-Code";
+
+/// `X-Error-Code` values caused by the consumer key, the access token or the user authorization
+const AUTH_ERROR_CODES: [u32; 5] = [107, 138, 152, 158, 159];
+
+pub(crate) fn handler_status(
+    status_code: StatusCode,
+    headers: &HeaderMap,
+    rate_limit: Option<&RateLimitInfo>,
+) -> Result<()> {
+    if status_code.is_success() {
+        return Ok(());
+    }
+
+    let code = headers
+        .get(X_ERROR_CODE)
+        .and_then(|code| code.to_str().ok())
+        .and_then(|code| code.trim().parse::<u32>().ok());
+
+    let x_error = headers
+        .get(X_ERROR)
+        .and_then(|message| message.to_str().ok())
+        .map(str::trim)
+        .filter(|message| !message.is_empty());
+
+    let message = |default: &str| x_error.unwrap_or(default).to_string();
+
+    if let Some(reset) = rate_limit.and_then(RateLimitInfo::exhausted_reset) {
+        if status_code == StatusCode::FORBIDDEN {
+            return Err(Error::RateLimited {
+                status: Some(status_code.as_u16()),
+                reset,
+                code,
+                message: message("The rate limit is exhausted."),
+            });
         }
     }
+
+    if status_code == StatusCode::UNAUTHORIZED
+        || code.map_or(false, |code| AUTH_ERROR_CODES.contains(&code))
+    {
+        return Err(Error::Auth {
+            status: Some(status_code.as_u16()),
+            code,
+            message: message("The consumer key or the access token was rejected."),
+        });
+    }
+
+    let message = match status_code {
+        StatusCode::BAD_REQUEST => message(
+            "Invalid request, please make sure you follow the documentation for proper syntax.",
+        ),
+        StatusCode::FORBIDDEN => message(
+            "User was authenticated, but access denied due to lack of permission or rate limiting.",
+        ),
+        StatusCode::SERVICE_UNAVAILABLE => {
+            return Err(Error::Maintenance {
+                code,
+                message: message("Pocket's sync server is down for scheduled maintenance."),
+            })
+        }
+        _ => message(
+            status_code
+                .canonical_reason()
+                .unwrap_or("Unexpected response status."),
+        ),
+    };
+
+    Err(Error::Http {
+        status: status_code.as_u16(),
+        code,
+        message,
+    })
+}
+
+pub(crate) fn decode<T: DeserializeOwned>(body: String) -> Result<T> {
+    serde_json::from_str(&body).map_err(|source| Error::Decode { body, source })
 }
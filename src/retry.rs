@@ -0,0 +1,150 @@
+use crate::error::Error;
+use reqwest::StatusCode;
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+/// Retry of transient failures with exponential backoff.
+///
+/// The n-th retry waits `base_delay * 2^(n-1)`, capped by `max_delay`; with `jitter`
+/// the delay is a random value between half and the whole of it.
+/// Requests that are not idempotent, such as adding an item, are only retried
+/// when `retry_non_idempotent` is set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub jitter: bool,
+    /// Response statuses that are worth another attempt
+    pub retry_statuses: Vec<StatusCode>,
+    /// Retry connection failures and timeouts
+    pub retry_transport_errors: bool,
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            retry_statuses: vec![
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            retry_transport_errors: true,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A policy that never retries
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn with_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub fn with_retry_statuses(mut self, retry_statuses: &[StatusCode]) -> Self {
+        self.retry_statuses = retry_statuses.to_vec();
+        self
+    }
+
+    pub fn with_retry_transport_errors(mut self, retry_transport_errors: bool) -> Self {
+        self.retry_transport_errors = retry_transport_errors;
+        self
+    }
+
+    pub fn with_retry_non_idempotent(mut self, retry_non_idempotent: bool) -> Self {
+        self.retry_non_idempotent = retry_non_idempotent;
+        self
+    }
+
+    /// Delay before the next attempt after `attempt` attempts ended with `status`,
+    /// or `None` if the request must not be sent again
+    pub(crate) fn delay_on_status(
+        &self,
+        status: StatusCode,
+        attempt: u32,
+        is_idempotent: bool,
+    ) -> Option<Duration> {
+        if !self.retry_statuses.contains(&status) {
+            return None;
+        }
+
+        self.delay(attempt, is_idempotent)
+    }
+
+    /// Same as `delay_on_status` for a request that failed without a response
+    pub(crate) fn delay_on_error(
+        &self,
+        err: &Error,
+        attempt: u32,
+        is_idempotent: bool,
+    ) -> Option<Duration> {
+        let is_transient = match err {
+            Error::Transport(err) => err.is_timeout() || err.is_connect(),
+            _ => false,
+        };
+
+        if !self.retry_transport_errors || !is_transient {
+            return None;
+        }
+
+        self.delay(attempt, is_idempotent)
+    }
+
+    fn delay(&self, attempt: u32, is_idempotent: bool) -> Option<Duration> {
+        if attempt >= self.max_attempts || !(is_idempotent || self.retry_non_idempotent) {
+            return None;
+        }
+
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let delay = self
+            .base_delay
+            .checked_mul(factor)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+
+        if self.jitter {
+            // Random value between 0.5 and 1.0 without pulling in a rand crate
+            let random = RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64;
+            Some(delay.mul_f64(0.5 + random / 2.0))
+        } else {
+            Some(delay)
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{
+    auth::{self, AccessToken},
+    builder::GetPocketBuilder,
+    client::GetPocket,
+    error::{Error, Result},
+};
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// Persistence of the access token between runs
+pub trait TokenStore: Send + Sync {
+    /// The stored token, `None` if there is none yet
+    fn load(&self) -> Result<Option<AccessToken>>;
+
+    fn save(&self, token: &AccessToken) -> Result<()>;
+
+    fn clear(&self) -> Result<()>;
+}
+
+/// Token stored as JSON in a file only its owner can read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTokenStore {
+    path: PathBuf,
+}
+
+impl FileTokenStore {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn write(&self, contents: &[u8]) -> io::Result<()> {
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+            options.mode(0o600);
+
+            // The mode only applies to a new file
+            if self.path.exists() {
+                fs::set_permissions(&self.path, fs::Permissions::from_mode(0o600))?;
+            }
+        }
+
+        let mut file = options.open(&self.path)?;
+        file.write_all(contents)?;
+        file.sync_all()
+    }
+}
+
+impl TokenStore for FileTokenStore {
+    fn load(&self) -> Result<Option<AccessToken>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(Error::Custom(Box::new(err))),
+        };
+
+        // Not a `Decode` error, its body would carry the token
+        serde_json::from_str(&contents)
+            .map(Some)
+            .map_err(|err| Error::Custom(Box::new(err)))
+    }
+
+    fn save(&self, token: &AccessToken) -> Result<()> {
+        let contents =
+            serde_json::to_vec(token).map_err(|err| Error::InvalidParams(err.to_string()))?;
+
+        self.write(&contents)
+            .map_err(|err| Error::Custom(Box::new(err)))
+    }
+
+    fn clear(&self) -> Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(Error::Custom(Box::new(err))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Token kept for the lifetime of the process, e.g. in tests
+#[derive(Debug, Default)]
+pub struct MemoryTokenStore {
+    token: Mutex<Option<AccessToken>>,
+}
+
+impl MemoryTokenStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<AccessToken>> {
+        self.token.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl TokenStore for MemoryTokenStore {
+    fn load(&self) -> Result<Option<AccessToken>> {
+        Ok(self.lock().clone())
+    }
+
+    fn save(&self, token: &AccessToken) -> Result<()> {
+        *self.lock() = Some(token.clone());
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<()> {
+        *self.lock() = None;
+        Ok(())
+    }
+}
+
+impl GetPocket {
+    /// Build the client with the token of `store`. Without a stored token the user
+    /// is authorized with `auth::loopback_login` and the new token is saved.
+    ///
+    /// ```ignore
+    /// let store = FileTokenStore::new("pocket_token.json");
+    /// let get_pocket = GetPocket::from_store(GetPocket::builder().consumer_key(consumer_key), &store, |auth_url| {
+    ///     println!("Open in browser next URL {auth_url}");
+    ///     Ok(())
+    /// })
+    /// .await?;
+    /// ```
+    pub async fn from_store<S, F>(builder: GetPocketBuilder, store: &S, opener: F) -> Result<Self>
+    where
+        S: TokenStore + ?Sized,
+        F: FnOnce(&str) -> Result<()>,
+    {
+        if let Some(token) = store.load()? {
+            return builder
+                .access_token(token.access_token.expose())
+                .username(token.username)
+                .build();
+        }
+
+        let get_pocket = auth::loopback_login(builder.build()?, opener).await?;
+
+        if let Some(access_token) = &get_pocket.token.access_token {
+            store.save(&AccessToken {
+                access_token: access_token.clone(),
+                username: get_pocket.account().unwrap_or_default().to_string(),
+            })?;
+        }
+
+        Ok(get_pocket)
+    }
+}
@@ -0,0 +1,223 @@
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Method, StatusCode,
+};
+use serde::Serialize;
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+#[derive(Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: HeaderMap,
+    pub body: Option<Vec<u8>>,
+}
+
+impl HttpRequest {
+    pub fn new(method: Method, url: impl Into<String>) -> Self {
+        Self {
+            method,
+            url: url.into(),
+            headers: HeaderMap::new(),
+            body: None,
+        }
+    }
+
+    pub fn post(url: impl Into<String>) -> Self {
+        Self::new(Method::POST, url)
+    }
+
+    pub fn with_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Serialize `body` as the JSON body of the request
+    pub fn json<T: Serialize + ?Sized>(mut self, body: &T) -> Result<Self> {
+        let body = serde_json::to_vec(body).map_err(|err| Error::InvalidParams(err.to_string()))?;
+
+        self.headers.insert(
+            reqwest::header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json; charset=UTF-8"),
+        );
+        self.body = Some(body);
+
+        Ok(self)
+    }
+
+    /// The body as text, for matching requests in tests
+    pub fn text(&self) -> Option<String> {
+        self.body
+            .as_deref()
+            .map(|body| String::from_utf8_lossy(body).into_owned())
+    }
+}
+
+// The bodies carry the consumer key and the access token
+impl fmt::Debug for HttpRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpRequest")
+            .field("method", &self.method)
+            .field("url", &self.url)
+            .field("headers", &self.headers)
+            .field("body", &self.body.as_ref().map(|body| body.len()))
+            .finish()
+    }
+}
+
+#[derive(Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl fmt::Debug for HttpResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpResponse")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .field("body", &self.body.len())
+            .finish()
+    }
+}
+
+impl HttpResponse {
+    pub fn new(status: StatusCode, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+
+    pub fn with_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+/// HTTP stack the client dispatches its requests through.
+///
+/// A transport only sends the request and returns the response as it is,
+/// error statuses, retries and rate limits are handled by `GetPocket`.
+#[async_trait]
+pub trait HttpTransport: fmt::Debug + Send + Sync {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse>;
+}
+
+/// The default transport
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+
+    pub fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+}
+
+impl From<reqwest::Client> for ReqwestTransport {
+    fn from(client: reqwest::Client) -> Self {
+        Self::new(client)
+    }
+}
+
+#[async_trait]
+impl HttpTransport for ReqwestTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let mut builder = self
+            .client
+            .request(request.method, request.url)
+            .headers(request.headers);
+
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+
+        let res = builder.send().await?;
+
+        Ok(HttpResponse {
+            status: res.status(),
+            headers: res.headers().clone(),
+            body: res.bytes().await?.to_vec(),
+        })
+    }
+}
+
+type Handler = dyn Fn(&HttpRequest) -> Result<HttpResponse> + Send + Sync;
+
+/// Transport answering every request with `handler` without any network,
+/// the requests are recorded for assertions.
+///
+/// ```ignore
+/// let transport = MemoryTransport::new(|_request| {
+///     Ok(HttpResponse::new(StatusCode::OK, r#"{"status": 1, "action_results": [true]}"#))
+/// });
+/// let get_pocket = GetPocket::builder()
+///     .consumer_key("consumer_key")
+///     .access_token("access_token")
+///     .transport(transport.clone())
+///     .build()?;
+/// ```
+#[derive(Clone)]
+pub struct MemoryTransport {
+    handler: Arc<Handler>,
+    requests: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+impl MemoryTransport {
+    pub fn new<F>(handler: F) -> Self
+    where
+        F: Fn(&HttpRequest) -> Result<HttpResponse> + Send + Sync + 'static,
+    {
+        Self {
+            handler: Arc::new(handler),
+            requests: Default::default(),
+        }
+    }
+
+    /// Every request sent so far, shared by the clones of this transport
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+}
+
+impl fmt::Debug for MemoryTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryTransport")
+            .field("requests", &self.requests)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl HttpTransport for MemoryTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let response = (self.handler)(&request);
+
+        self.requests
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push(request);
+
+        response
+    }
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "blocking")]
+
+mod test_helper;
+
+use getpocket::{
+    blocking, modifying::RequestArchive, retrieving::ListQuery, HttpResponse, MemoryTransport,
+};
+use reqwest::StatusCode;
+
+fn blocking_get_pocket(transport: &MemoryTransport) -> blocking::GetPocket {
+    blocking::GetPocket::from_async(test_helper::memory_get_pocket(transport)).unwrap()
+}
+
+#[test]
+fn test_blocking_client() {
+    let transport = MemoryTransport::new(|request| {
+        let body = match request.url.contains("/v3/get") {
+            true => {
+                r#"{"status": 1, "complete": 1, "list": {"1": {"item_id": "1"}, "2": {"item_id": "2"}}, "error": null, "since": 1700000000, "total": "2"}"#
+            }
+            false => r#"{"action_results": [true, true], "status": 1}"#,
+        };
+
+        Ok(HttpResponse::new(StatusCode::OK, body))
+    });
+
+    let get_pocket = blocking_get_pocket(&transport);
+
+    let record = get_pocket.list(&ListQuery::new()).unwrap();
+    assert_eq!(record.list.len(), 2);
+
+    let items: Vec<_> = get_pocket
+        .list_iter(ListQuery::new().count(10))
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(items.len(), 2);
+
+    let resp = get_pocket
+        .bulk_archive(&[RequestArchive::new(1), RequestArchive::new(2)])
+        .unwrap();
+    assert!(resp.is_success());
+
+    assert_eq!(transport.requests().len(), 3);
+}
+
+#[test]
+fn test_blocking_client_dropped_in_async_context() {
+    let transport = MemoryTransport::new(|_| {
+        Ok(HttpResponse::new(
+            StatusCode::OK,
+            r#"{"action_results": [true], "status": 1}"#,
+        ))
+    });
+
+    let get_pocket = blocking_get_pocket(&transport);
+    assert!(get_pocket.archive(1).unwrap().is_success);
+
+    // The last clone may be dropped by async code, e.g. a client moved into a task
+    let clone = get_pocket.clone();
+    drop(get_pocket);
+
+    tokio::runtime::Runtime::new()
+        .unwrap()
+        .block_on(async move {
+            drop(clone);
+        });
+}
@@ -1,43 +1,98 @@
-use getpocket::GetPocket;
+// Every test crate and example only uses some of these helpers
+#![allow(dead_code)]
+
+use getpocket::{Config, FileTokenStore, GetPocket, HttpRequest, HttpResponse, MemoryTransport};
 use lazy_static::lazy_static;
-use std::{sync::Mutex, thread, time};
-use webbrowser;
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
 
 lazy_static! {
     #[derive(Debug)]
     static ref GETPOCKET_INSTANCE: Mutex<Option<GetPocket>> = Mutex::new(None);
 }
 
-use std::{env, fs, path};
+use std::env;
 
 pub async fn init_get_pocket() -> GetPocket {
-    let consumer_key = env::var("GET_POCKET_CONSUMER_KEY").expect("ENV must be set");
-    let redirect_url = env::var("GET_POCKET_REDIRECT_URL").expect("ENV must be set");
-    let tmp_dir = std::env::temp_dir().display().to_string();
-    let cfg_path = format!("{}/get_pocket_access_token", tmp_dir);
-    match path::Path::new(&cfg_path).exists() {
-        true => {
-            let access_token: String = fs::read_to_string(cfg_path).unwrap();
-            GetPocket::new(consumer_key, redirect_url, access_token)
-                .await
-                .unwrap()
-        }
-        false => GetPocket::init(
-            consumer_key,
-            redirect_url,
-            |access_token| {
-                fs::write(cfg_path, access_token).unwrap();
-            },
-            |auth_url| {
-                let ret = webbrowser::open(auth_url).is_ok();
-
-                let wait_time = time::Duration::from_millis(6000);
-                thread::sleep(wait_time);
-
-                Ok(ret)
-            },
-        )
-        .await
-        .unwrap(),
+    let builder = Config::from_env().unwrap().into_builder().unwrap();
+    let store = FileTokenStore::new(env::temp_dir().join("get_pocket_access_token.json"));
+
+    GetPocket::from_store(builder, &store, |auth_url| {
+        webbrowser::open(auth_url).map_err(|err| getpocket::Error::Custom(Box::new(err)))
+    })
+    .await
+    .unwrap()
+}
+
+pub fn mock_server(count: usize, status: u16, headers: &[(&str, &str)], body: &str) -> String {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    let mut response = format!(
+        "HTTP/1.1 {status} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        body.len()
+    );
+    for (name, value) in headers {
+        response.push_str(&format!("{name}: {value}\r\n"));
     }
+    response.push_str(&format!("\r\n{body}"));
+
+    thread::spawn(move || {
+        for stream in listener.incoming().take(count) {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+
+    base_url
+}
+
+/// Authorized client sending its requests to `transport`
+pub fn memory_get_pocket(transport: &MemoryTransport) -> GetPocket {
+    GetPocket::builder()
+        .consumer_key("consumer_key")
+        .access_token("access_token")
+        .transport(transport.clone())
+        .build()
+        .unwrap()
+}
+
+/// Transport answering the first `failures` requests with `failure` and the next ones with `then`
+pub fn failing_transport<F>(failures: usize, failure: HttpResponse, then: F) -> MemoryTransport
+where
+    F: Fn(&HttpRequest) -> HttpResponse + Send + Sync + 'static,
+{
+    let sent = AtomicUsize::new(0);
+
+    MemoryTransport::new(move |request| {
+        Ok(match sent.fetch_add(1, Ordering::SeqCst) < failures {
+            true => failure.clone(),
+            false => then(request),
+        })
+    })
 }
@@ -1,9 +1,10 @@
 mod test_helper;
 
+use futures::StreamExt;
 use getpocket::{
-    adding::AddingExt, modifying::ModifyingExt, retrieving::RetrievingExt, GetPocket,
-    RecordItemContentType, RecordItemDetailType, RecordItemFavorite, RecordItemSort,
-    RecordItemState, RecordItemTag,
+    adding::AddingExt, batch::*, bulk::*, modifying::*, retrieving::*, sync::*, tags::*, GetPocket,
+    ItemStatus, RecordItemContentType, RecordItemDetailType, RecordItemFavorite, RecordItemSort,
+    RecordItemState, RecordItemTag, Secret,
 };
 use serde::{Deserialize, Serialize};
 use tokio::test;
@@ -14,18 +15,11 @@ async fn test_retrieve_all_archive_items() {
 
     // Test case: Retrieve all archive items
     let r = get_pocket
-        .list_of_items_with_params(
-            RecordItemState::Archive,
-            RecordItemFavorite::default(),
-            RecordItemTag::default(),
-            RecordItemContentType::default(),
-            RecordItemSort::default(),
-            RecordItemDetailType::default(),
-            None,
-            None,
-            None,
-            0,
-            1,
+        .list(
+            &ListQuery::new()
+                .state(RecordItemState::Archive)
+                .offset(0)
+                .count(1),
         )
         .await;
     assert!(r.is_ok());
@@ -37,18 +31,11 @@ async fn test_retrieve_all_unread_items() {
 
     // Test case: Retrieve all unread items
     let r = get_pocket
-        .list_of_items_with_params(
-            RecordItemState::Unread,
-            RecordItemFavorite::default(),
-            RecordItemTag::default(),
-            RecordItemContentType::default(),
-            RecordItemSort::default(),
-            RecordItemDetailType::default(),
-            None,
-            None,
-            None,
-            0,
-            1,
+        .list(
+            &ListQuery::new()
+                .state(RecordItemState::Unread)
+                .offset(0)
+                .count(1),
         )
         .await;
     assert!(r.is_ok());
@@ -60,18 +47,11 @@ async fn test_retrieve_all_state_items() {
 
     // Test case: Retrieve all items
     let r = get_pocket
-        .list_of_items_with_params(
-            RecordItemState::All,
-            RecordItemFavorite::default(),
-            RecordItemTag::default(),
-            RecordItemContentType::default(),
-            RecordItemSort::default(),
-            RecordItemDetailType::default(),
-            None,
-            None,
-            None,
-            0,
-            1,
+        .list(
+            &ListQuery::new()
+                .state(RecordItemState::All)
+                .offset(0)
+                .count(1),
         )
         .await;
     assert!(r.is_ok());
@@ -83,18 +63,11 @@ async fn test_retrieve_favorited_items() {
 
     // Test case: Retrieve favorited items
     let r = get_pocket
-        .list_of_items_with_params(
-            RecordItemState::default(),
-            RecordItemFavorite::Favorited,
-            RecordItemTag::default(),
-            RecordItemContentType::default(),
-            RecordItemSort::default(),
-            RecordItemDetailType::default(),
-            None,
-            None,
-            None,
-            0,
-            1,
+        .list(
+            &ListQuery::new()
+                .favorite(RecordItemFavorite::Favorited)
+                .offset(0)
+                .count(1),
         )
         .await;
     assert!(r.is_ok());
@@ -106,18 +79,11 @@ async fn test_retrieve_unfavorited_items() {
 
     // Test case: Retrieve unfavorited items
     let r = get_pocket
-        .list_of_items_with_params(
-            RecordItemState::default(),
-            RecordItemFavorite::Unfavorited,
-            RecordItemTag::default(),
-            RecordItemContentType::default(),
-            RecordItemSort::default(),
-            RecordItemDetailType::default(),
-            None,
-            None,
-            None,
-            0,
-            1,
+        .list(
+            &ListQuery::new()
+                .favorite(RecordItemFavorite::Unfavorited)
+                .offset(0)
+                .count(1),
         )
         .await;
     assert!(r.is_ok());
@@ -129,18 +95,11 @@ async fn test_retrieve_untagged_items() {
 
     // Test case: Retrieve untagged items
     let r = get_pocket
-        .list_of_items_with_params(
-            RecordItemState::default(),
-            RecordItemFavorite::default(),
-            RecordItemTag::Untagged,
-            RecordItemContentType::default(),
-            RecordItemSort::default(),
-            RecordItemDetailType::default(),
-            None,
-            None,
-            None,
-            0,
-            1,
+        .list(
+            &ListQuery::new()
+                .tag(RecordItemTag::Untagged)
+                .offset(0)
+                .count(1),
         )
         .await;
     assert!(r.is_ok());
@@ -161,18 +120,11 @@ async fn test_retrieve_tagged_items() {
 
     // Test case: Retrieve tagged items
     let resp = get_pocket
-        .list_of_items_with_params(
-            RecordItemState::default(),
-            RecordItemFavorite::default(),
-            RecordItemTag::TagName("rust"),
-            RecordItemContentType::default(),
-            RecordItemSort::default(),
-            RecordItemDetailType::default(),
-            None,
-            None,
-            None,
-            0,
-            1,
+        .list(
+            &ListQuery::new()
+                .tag(RecordItemTag::TagName("rust"))
+                .offset(0)
+                .count(1),
         )
         .await;
 
@@ -185,18 +137,11 @@ async fn test_retrieve_all_content_type_items() {
 
     // Test case: Retrieve all content type items
     let r = get_pocket
-        .list_of_items_with_params(
-            RecordItemState::default(),
-            RecordItemFavorite::default(),
-            RecordItemTag::default(),
-            RecordItemContentType::All,
-            RecordItemSort::default(),
-            RecordItemDetailType::default(),
-            None,
-            None,
-            None,
-            0,
-            1,
+        .list(
+            &ListQuery::new()
+                .content_type(RecordItemContentType::All)
+                .offset(0)
+                .count(1),
         )
         .await;
     assert!(r.is_ok());
@@ -208,18 +153,11 @@ async fn test_retrieve_video_content_type_items() {
 
     // Test case: Retrieve video content type items
     let r = get_pocket
-        .list_of_items_with_params(
-            RecordItemState::default(),
-            RecordItemFavorite::default(),
-            RecordItemTag::default(),
-            RecordItemContentType::Video,
-            RecordItemSort::default(),
-            RecordItemDetailType::default(),
-            None,
-            None,
-            None,
-            0,
-            1,
+        .list(
+            &ListQuery::new()
+                .content_type(RecordItemContentType::Video)
+                .offset(0)
+                .count(1),
         )
         .await;
     assert!(r.is_ok());
@@ -231,18 +169,11 @@ async fn test_retrieve_article_content_type_items() {
 
     // Test case: Retrieve article content type items
     let r = get_pocket
-        .list_of_items_with_params(
-            RecordItemState::default(),
-            RecordItemFavorite::default(),
-            RecordItemTag::default(),
-            RecordItemContentType::Article,
-            RecordItemSort::default(),
-            RecordItemDetailType::default(),
-            None,
-            None,
-            None,
-            0,
-            1,
+        .list(
+            &ListQuery::new()
+                .content_type(RecordItemContentType::Article)
+                .offset(0)
+                .count(1),
         )
         .await;
     assert!(r.is_ok());
@@ -263,18 +194,11 @@ async fn test_retrieve_image_content_type_items() {
 
     // Test case: Retrieve image content type items
     let r = get_pocket
-        .list_of_items_with_params(
-            RecordItemState::default(),
-            RecordItemFavorite::default(),
-            RecordItemTag::default(),
-            RecordItemContentType::Image,
-            RecordItemSort::default(),
-            RecordItemDetailType::default(),
-            None,
-            None,
-            None,
-            0,
-            1,
+        .list(
+            &ListQuery::new()
+                .content_type(RecordItemContentType::Image)
+                .offset(0)
+                .count(1),
         )
         .await;
     assert!(r.is_ok());
@@ -286,18 +210,11 @@ async fn test_retrieve_simple_items() {
 
     // Test case: Retrieve simple items
     let r = get_pocket
-        .list_of_items_with_params(
-            RecordItemState::default(),
-            RecordItemFavorite::default(),
-            RecordItemTag::default(),
-            RecordItemContentType::default(),
-            RecordItemSort::default(),
-            RecordItemDetailType::Simple,
-            None,
-            None,
-            None,
-            0,
-            1,
+        .list(
+            &ListQuery::new()
+                .detail_type(RecordItemDetailType::Simple)
+                .offset(0)
+                .count(1),
         )
         .await;
     assert!(r.is_ok());
@@ -309,18 +226,11 @@ async fn test_retrieve_complete_items() {
 
     // Test case: Retrieve complete items
     let r = get_pocket
-        .list_of_items_with_params(
-            RecordItemState::default(),
-            RecordItemFavorite::default(),
-            RecordItemTag::default(),
-            RecordItemContentType::default(),
-            RecordItemSort::default(),
-            RecordItemDetailType::Complete,
-            None,
-            None,
-            None,
-            0,
-            1,
+        .list(
+            &ListQuery::new()
+                .detail_type(RecordItemDetailType::Complete)
+                .offset(0)
+                .count(1),
         )
         .await;
     assert!(r.is_ok());
@@ -349,6 +259,484 @@ async fn test_retrieve_all_items() {
     assert!(r.is_ok());
 }
 
+#[test]
+async fn test_list_stream_items() {
+    let get_pocket: GetPocket = test_helper::init_get_pocket().await;
+
+    // Test case: Page through all items, two per request
+    let items: Vec<_> = get_pocket
+        .list_stream(ListQuery::new().state(RecordItemState::All).count(2))
+        .take(5)
+        .collect()
+        .await;
+
+    assert!(items.len() <= 5);
+    assert!(items.iter().all(|item| item.is_ok()));
+}
+
+/// Offline /v3/get serving `items` page by page, capped at 30 items per call like Pocket
+fn paged_transport(items: Vec<serde_json::Value>, with_total: bool) -> getpocket::MemoryTransport {
+    use getpocket::{HttpResponse, MemoryTransport};
+    use reqwest::StatusCode;
+
+    MemoryTransport::new(move |request| {
+        let params: serde_json::Value = serde_json::from_str(&request.text().unwrap()).unwrap();
+        let offset = params["offset"].as_u64().unwrap_or(0) as usize;
+        let count = params["count"].as_u64().unwrap_or(30).min(30) as usize;
+
+        let list: serde_json::Map<_, _> = items
+            .iter()
+            .skip(offset)
+            .take(count)
+            .enumerate()
+            .map(|(i, item)| {
+                let mut item = item.clone();
+                item["sort_id"] = (offset + i).into();
+                (item["item_id"].to_string(), item)
+            })
+            .collect();
+
+        let mut body = serde_json::json!({
+            "status": 1,
+            "complete": 1,
+            "list": list,
+            "error": null,
+            "since": 1700000000,
+        });
+        if with_total {
+            body["total"] = items.len().to_string().into();
+        }
+
+        Ok(HttpResponse::new(StatusCode::OK, body.to_string()))
+    })
+}
+
+#[test]
+async fn test_list_stream_capped_pages() {
+    let items: Vec<_> = (1..=70)
+        .map(|item_id| serde_json::json!({ "item_id": item_id.to_string() }))
+        .collect();
+
+    for with_total in [true, false] {
+        let transport = paged_transport(items.clone(), with_total);
+        let get_pocket = test_helper::memory_get_pocket(&transport);
+
+        // Test case: Pocket returns 30 of the 100 requested items per page
+        let item_ids: Vec<i64> = get_pocket
+            .list_stream(ListQuery::new().count(100))
+            .map(|item| item.unwrap().item_id)
+            .collect()
+            .await;
+        assert_eq!(item_ids, (1..=70).collect::<Vec<_>>());
+
+        // 30 + 30 + 10 items, plus the empty page without a total
+        let requests = transport.requests();
+        assert_eq!(requests.len(), if with_total { 3 } else { 4 });
+        assert!(requests[1].text().unwrap().contains(r#""offset":30"#));
+        assert!(requests[2].text().unwrap().contains(r#""offset":60"#));
+    }
+}
+
+#[test]
+async fn test_sync_items() {
+    let get_pocket: GetPocket = test_helper::init_get_pocket().await;
+
+    // Test case: Initial sync followed by an incremental one
+    let mut state = SyncState::new();
+    let resp_sync = get_pocket.sync(&mut state).await;
+    assert!(resp_sync.is_ok());
+    assert!(!state.is_initial());
+
+    let item_id = add_new_item(&get_pocket, "https://getpocket.com/developer/docs/v3/add").await;
+    let _ = get_pocket.archive(item_id).await;
+
+    let resp_sync = get_pocket.sync(&mut state).await;
+    assert!(resp_sync.is_ok());
+    assert!(resp_sync
+        .unwrap()
+        .archived
+        .iter()
+        .any(|item| item.item_id == item_id));
+
+    let _ = get_pocket.delete(item_id).await;
+}
+
+#[test]
+async fn test_sync_capped_pages() {
+    // Ids by 4: deleted, archived, unread saved before the previous sync, unread saved after it
+    let items: Vec<_> = (1..=40)
+        .map(|item_id: i64| match item_id % 4 {
+            0 => serde_json::json!({ "item_id": item_id.to_string(), "status": "2" }),
+            1 => serde_json::json!({ "item_id": item_id.to_string(), "status": "1", "time_added": "1500000000" }),
+            2 => serde_json::json!({ "item_id": item_id.to_string(), "status": "0", "time_added": "1500000000" }),
+            _ => serde_json::json!({ "item_id": item_id.to_string(), "status": "0", "time_added": "1650000000" }),
+        })
+        .collect();
+
+    let transport = paged_transport(items, false);
+    let get_pocket = test_helper::memory_get_pocket(&transport);
+
+    // Test case: Every change is returned although Pocket caps the pages at 30 items
+    let mut state = SyncState::from_since(1600000000);
+    let changes = get_pocket.sync(&mut state).await.unwrap();
+
+    let ids = |items: &[getpocket::Item]| {
+        let mut ids: Vec<i64> = items.iter().map(|item| item.item_id).collect();
+        ids.sort();
+        ids
+    };
+    let expected = |rem: i64| (1..=40).filter(|id| id % 4 == rem).collect::<Vec<i64>>();
+
+    assert_eq!(changes.len(), 40);
+    assert_eq!(ids(&changes.added), expected(3));
+    assert_eq!(ids(&changes.updated), expected(2));
+    assert_eq!(ids(&changes.archived), expected(1));
+    let mut deleted = changes.deleted.clone();
+    deleted.sort();
+    assert_eq!(deleted, expected(0));
+
+    assert_eq!(state, SyncState::from_since(1700000000));
+    assert_eq!(transport.requests().len(), 3);
+    assert!(transport.requests()[0]
+        .text()
+        .unwrap()
+        .contains(r#""since":1600000000"#));
+
+    // Test case: The initial sync returns every unread item as added
+    let changes = get_pocket.sync(&mut SyncState::new()).await.unwrap();
+    assert_eq!(changes.added.len(), 20);
+    assert!(changes.updated.is_empty());
+}
+
+#[cfg(feature = "cache")]
+#[test]
+async fn test_cache_apply_changes() {
+    use getpocket::{cache::Cache, Item, ItemTag};
+
+    let item = |item_id: i64, url: &str, tags: &[&str]| Item {
+        item_id,
+        given_url: Some(url.to_string()),
+        time_added: Some(item_id),
+        tags: tags
+            .iter()
+            .map(|tag| {
+                let item_tag = ItemTag {
+                    item_id: Some(item_id),
+                    tag: tag.to_string(),
+                };
+                (tag.to_string(), item_tag)
+            })
+            .collect(),
+        ..Item::default()
+    };
+
+    let cache = Cache::open_in_memory().unwrap();
+    assert!(cache.sync_state().unwrap().is_initial());
+
+    cache
+        .apply(&SyncChanges {
+            added: vec![
+                item(1, "https://www.rust-lang.org/learn", &["rust"]),
+                Item {
+                    favorite: true,
+                    ..item(2, "https://blog.rust-lang.org/", &["rust", "blog"])
+                },
+                item(3, "https://getpocket.com/developer/", &[]),
+            ],
+            since: 100,
+            ..SyncChanges::default()
+        })
+        .unwrap();
+
+    assert_eq!(cache.len().unwrap(), 3);
+    assert_eq!(cache.sync_state().unwrap().since, Some(100));
+    assert_eq!(cache.tags().unwrap(), vec!["blog", "rust"]);
+    assert_eq!(cache.items_by_tag("rust").unwrap().len(), 2);
+    assert_eq!(cache.items_by_domain("rust-lang.org").unwrap().len(), 2);
+    // `%` and `_` are not wildcards
+    assert!(cache.items_by_domain("rust_lang.org").unwrap().is_empty());
+    assert!(cache.items_by_domain("%").unwrap().is_empty());
+    assert_eq!(cache.favorites().unwrap()[0].item_id, 2);
+
+    cache
+        .apply(&SyncChanges {
+            archived: vec![Item {
+                status: ItemStatus::Archived,
+                ..item(1, "https://www.rust-lang.org/learn", &[])
+            }],
+            deleted: vec![3],
+            since: 200,
+            ..SyncChanges::default()
+        })
+        .unwrap();
+
+    assert_eq!(cache.len().unwrap(), 2);
+    assert!(cache.item(3).unwrap().is_none());
+    assert_eq!(cache.archived().unwrap()[0].item_id, 1);
+    assert_eq!(cache.unread().unwrap()[0].item_id, 2);
+    assert_eq!(cache.items_by_tag("rust").unwrap().len(), 1);
+    assert_eq!(cache.sync_state().unwrap().since, Some(200));
+}
+
+#[test]
+async fn test_rate_limit_from_headers() {
+    use getpocket::RateLimitInfo;
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    assert!(RateLimitInfo::from_headers(&HeaderMap::new()).is_none());
+
+    let mut headers = HeaderMap::new();
+    headers.insert("X-Limit-User-Limit", HeaderValue::from_static("320"));
+    headers.insert("X-Limit-User-Remaining", HeaderValue::from_static("318"));
+    headers.insert("X-Limit-User-Reset", HeaderValue::from_static("3600"));
+    headers.insert("X-Limit-Key-Limit", HeaderValue::from_static("10000"));
+    headers.insert("X-Limit-Key-Remaining", HeaderValue::from_static("9990"));
+    headers.insert("X-Limit-Key-Reset", HeaderValue::from_static("120"));
+
+    let rate_limit = RateLimitInfo::from_headers(&headers).unwrap();
+    assert_eq!(rate_limit.user_limit, Some(320));
+    assert_eq!(rate_limit.user_remaining, Some(318));
+    assert_eq!(rate_limit.user_reset, Some(3600));
+    assert_eq!(rate_limit.key_limit, Some(10000));
+    assert_eq!(rate_limit.key_remaining, Some(9990));
+    assert_eq!(rate_limit.key_reset, Some(120));
+    assert!(rate_limit.key_reset_at() < rate_limit.user_reset_at());
+}
+
+#[test]
+async fn test_last_rate_limit() {
+    let get_pocket: GetPocket = test_helper::init_get_pocket().await;
+
+    let r = get_pocket.list_of_items_paginate(0, 1).await;
+    assert!(r.is_ok());
+    assert!(get_pocket.last_rate_limit().is_some());
+}
+
+#[test]
+async fn test_throttled_requests() {
+    let get_pocket: GetPocket = test_helper::init_get_pocket()
+        .await
+        .with_throttle(getpocket::Throttle::new().with_reserve(10));
+
+    for _ in 0..2 {
+        let r = get_pocket.list_of_items_paginate(0, 1).await;
+        assert!(r.is_ok());
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_throttle_waits_after_rejection() {
+    use getpocket::{HttpResponse, MemoryTransport, Throttle};
+    use reqwest::StatusCode;
+    use std::time::Duration;
+
+    fn rejecting_transport(rejections: usize, reset: Option<&'static str>) -> MemoryTransport {
+        let mut rejection = HttpResponse::new(StatusCode::FORBIDDEN, "").with_header(
+            "X-Limit-User-Remaining".parse().unwrap(),
+            "0".parse().unwrap(),
+        );
+        if let Some(reset) = reset {
+            rejection = rejection.with_header(
+                "X-Limit-User-Reset".parse().unwrap(),
+                reset.parse().unwrap(),
+            );
+        }
+
+        test_helper::failing_transport(rejections, rejection, |_| {
+            HttpResponse::new(StatusCode::OK, r#"{"action_results": [true], "status": 1}"#)
+        })
+    }
+
+    // Test case: Without a reset the call is sent again after a backoff, not right away
+    let transport = rejecting_transport(1, None);
+    let started = tokio::time::Instant::now();
+    assert!(
+        test_helper::memory_get_pocket(&transport)
+            .with_throttle(Throttle::new())
+            .archive(1)
+            .await
+            .unwrap()
+            .is_success
+    );
+    assert_eq!(transport.requests().len(), 2);
+    assert!(started.elapsed() >= Duration::from_secs(1));
+
+    let transport = rejecting_transport(usize::MAX, None);
+    let started = tokio::time::Instant::now();
+    let res = test_helper::memory_get_pocket(&transport)
+        .with_throttle(Throttle::new())
+        .archive(1)
+        .await;
+    assert!(matches!(res, Err(getpocket::Error::RateLimited { .. })));
+    assert_eq!(transport.requests().len(), 3);
+    // 1s, then 2s
+    assert!(started.elapsed() >= Duration::from_secs(3));
+
+    // Test case: With a reset the call waits for it
+    let transport = rejecting_transport(1, Some("2"));
+    let started = tokio::time::Instant::now();
+    assert!(
+        test_helper::memory_get_pocket(&transport)
+            .with_throttle(Throttle::new())
+            .archive(1)
+            .await
+            .unwrap()
+            .is_success
+    );
+    assert_eq!(transport.requests().len(), 2);
+    assert!(started.elapsed() >= Duration::from_millis(1900));
+}
+
+#[test]
+async fn test_error_is_owned() {
+    fn assert_error<E: std::error::Error + Send + Sync + 'static>(_: &E) {}
+
+    let err = getpocket::Error::RateLimited {
+        status: Some(403),
+        reset: Some(60),
+        code: None,
+        message: "The rate limit is exhausted.".to_string(),
+    };
+    assert_error(&err);
+
+    let handle = tokio::spawn(async move { err });
+    match handle.await.unwrap() {
+        getpocket::Error::RateLimited { reset, .. } => assert_eq!(reset, Some(60)),
+        err => panic!("unexpected error {err}"),
+    }
+}
+
+#[test]
+async fn test_error_code() {
+    let err = getpocket::Error::Auth {
+        status: Some(403),
+        code: Some(152),
+        message: "Invalid consumer key.".to_string(),
+    };
+
+    assert_eq!(err.code(), Some(152));
+    assert_eq!(err.status(), Some(403));
+    assert_eq!(
+        err.to_string(),
+        "Problem authenticating the user (code 152). Invalid consumer key."
+    );
+
+    let err = getpocket::Error::Http {
+        status: 404,
+        code: None,
+        message: "Not Found".to_string(),
+    };
+
+    assert_eq!(err.code(), None);
+    assert_eq!(err.status(), Some(404));
+
+    // Test case: X-Error and X-Error-Code are kept for 503 and exhausted 403 responses
+    async fn error_of(
+        status: u16,
+        headers: &'static [(&'static str, &'static str)],
+    ) -> getpocket::Error {
+        use getpocket::{HttpResponse, MemoryTransport};
+        use reqwest::StatusCode;
+
+        let transport = MemoryTransport::new(move |_| {
+            let mut res = HttpResponse::new(StatusCode::from_u16(status).unwrap(), "");
+            for (name, value) in headers {
+                res = res.with_header(name.parse().unwrap(), value.parse().unwrap());
+            }
+            Ok(res)
+        });
+
+        test_helper::memory_get_pocket(&transport)
+            .archive(229279689)
+            .await
+            .unwrap_err()
+    }
+
+    let err = error_of(
+        503,
+        &[
+            ("X-Error", "Pocket is in maintenance."),
+            ("X-Error-Code", "199"),
+        ],
+    )
+    .await;
+    assert!(matches!(
+        &err,
+        getpocket::Error::Maintenance { code: Some(199), message } if message == "Pocket is in maintenance."
+    ));
+    assert_eq!(err.status(), Some(503));
+    assert_eq!(err.code(), Some(199));
+
+    let err = error_of(
+        403,
+        &[
+            ("X-Error", "User rate limit exceeded."),
+            ("X-Error-Code", "199"),
+            ("X-Limit-User-Remaining", "0"),
+            ("X-Limit-User-Reset", "120"),
+        ],
+    )
+    .await;
+    assert!(matches!(
+        &err,
+        getpocket::Error::RateLimited { status: Some(403), reset: Some(120), code: Some(199), message } if message == "User rate limit exceeded."
+    ));
+    assert_eq!(err.status(), Some(403));
+    assert_eq!(
+        err.to_string(),
+        "Rate limit exceeded (code 199), it resets in 120 seconds. User rate limit exceeded."
+    );
+    // Test case: The status tells a rejected access token from a rejected consumer key
+    let err = error_of(401, &[("X-Error-Code", "107")]).await;
+    assert!(matches!(err, getpocket::Error::Auth { .. }));
+    assert_eq!((err.status(), err.code()), (Some(401), Some(107)));
+
+    let err = error_of(403, &[("X-Error-Code", "152")]).await;
+    assert!(matches!(err, getpocket::Error::Auth { .. }));
+    assert_eq!((err.status(), err.code()), (Some(403), Some(152)));
+}
+
+#[test]
+async fn test_list_query_serialize() {
+    let query = ListQuery::new()
+        .state(RecordItemState::All)
+        .favorite(RecordItemFavorite::Favorited)
+        .tag(RecordItemTag::Untagged)
+        .content_type(RecordItemContentType::Article)
+        .sort(RecordItemSort::Newest)
+        .detail_type(RecordItemDetailType::Complete)
+        .search("")
+        .domain("getpocket.com")
+        .since(1700000000)
+        .offset(10)
+        .count(5)
+        .total(true);
+
+    assert_eq!(
+        serde_json::to_value(&query).unwrap(),
+        serde_json::json!({
+            "state": "all",
+            "favorite": 1,
+            "tag": "_untagged_",
+            "contentType": "article",
+            "sort": "newest",
+            "detailType": "complete",
+            "domain": "getpocket.com",
+            "since": 1700000000,
+            "offset": 10,
+            "count": 5,
+            "total": 1,
+        })
+    );
+
+    let next_page = query.clone().offset(15);
+    assert_ne!(next_page, query);
+    assert_eq!(
+        serde_json::to_value(ListQuery::new()).unwrap(),
+        serde_json::json!({})
+    );
+}
+
 #[test]
 async fn test_send_params_direct() {
     let get_pocket: GetPocket = test_helper::init_get_pocket().await;
@@ -425,15 +813,987 @@ async fn test_delete_item() {
     assert!(resp_delete.is_ok());
 }
 
+#[test]
+async fn test_tags_add_item() {
+    let get_pocket: GetPocket = test_helper::init_get_pocket().await;
+    let item_id = add_new_item(&get_pocket, "https://getpocket.com/developer/docs/v3/add").await;
+
+    let resp_tags_add = get_pocket
+        .tags_add(&RequestAddTags::new(item_id, &["rust", "pocket"]))
+        .await;
+    assert!(resp_tags_add.is_ok());
+
+    let _ = get_pocket.delete(item_id).await;
+}
+
+#[test]
+async fn test_tags_remove_item() {
+    let get_pocket: GetPocket = test_helper::init_get_pocket().await;
+    let item_id = add_new_item(&get_pocket, "https://getpocket.com/developer/docs/v3/add").await;
+    let _ = get_pocket
+        .tags_add(&RequestAddTags::new(item_id, &["rust", "pocket"]))
+        .await;
+
+    let resp_tags_remove = get_pocket
+        .tags_remove(&RequestRemoveTags::new(item_id, &["pocket"]))
+        .await;
+    assert!(resp_tags_remove.is_ok());
+
+    let _ = get_pocket.delete(item_id).await;
+}
+
+#[test]
+async fn test_tags_replace_item() {
+    let get_pocket: GetPocket = test_helper::init_get_pocket().await;
+    let item_id = add_new_item(&get_pocket, "https://getpocket.com/developer/docs/v3/add").await;
+
+    let resp_tags_replace = get_pocket
+        .tags_replace(&RequestReplaceTags::new(item_id, &["docs"]))
+        .await;
+    assert!(resp_tags_replace.is_ok());
+
+    let _ = get_pocket.delete(item_id).await;
+}
+
+#[test]
+async fn test_tags_clear_item() {
+    let get_pocket: GetPocket = test_helper::init_get_pocket().await;
+    let item_id = add_new_item(&get_pocket, "https://getpocket.com/developer/docs/v3/add").await;
+    let _ = get_pocket
+        .tags_add(&RequestAddTags::new(item_id, &["rust"]))
+        .await;
+
+    let resp_tags_clear = get_pocket.tags_clear(&RequestClearTags::new(item_id)).await;
+    assert!(resp_tags_clear.is_ok());
+
+    let _ = get_pocket.delete(item_id).await;
+}
+
+#[test]
+async fn test_tag_rename_and_delete() {
+    let get_pocket: GetPocket = test_helper::init_get_pocket().await;
+    let item_id = add_new_item(&get_pocket, "https://getpocket.com/developer/docs/v3/add").await;
+    let _ = get_pocket
+        .tags_add(&RequestAddTags::new(item_id, &["getpocket-rs-old"]))
+        .await;
+
+    let resp_tag_rename = get_pocket
+        .tag_rename(&RequestRenameTags::new(
+            "getpocket-rs-old",
+            "getpocket-rs-new",
+        ))
+        .await;
+    assert!(resp_tag_rename.is_ok());
+
+    let resp_tag_delete = get_pocket
+        .tag_delete(&RequestDeleteTags::new("getpocket-rs-new"))
+        .await;
+    assert!(resp_tag_delete.is_ok());
+
+    let _ = get_pocket.delete(item_id).await;
+}
+
+#[test]
+async fn test_bulk_add_items() {
+    let get_pocket: GetPocket = test_helper::init_get_pocket().await;
+
+    let params = [
+        BulkRequestAdd::new("https://getpocket.com/developer/docs/v3/add").with_tags(&["docs"]),
+        BulkRequestAdd::new("https://getpocket.com/developer/docs/v3/modify"),
+    ];
+    let resp_bulk_add = get_pocket.bulk_add(&params).await;
+    assert!(resp_bulk_add.is_ok());
+
+    let resp_bulk_add = resp_bulk_add.unwrap();
+    assert_eq!(resp_bulk_add.zip(&params).len(), params.len());
+    assert!(resp_bulk_add.failed(&params).is_empty());
+}
+
+#[test]
+async fn test_bulk_archive_and_delete_items() {
+    let get_pocket: GetPocket = test_helper::init_get_pocket().await;
+    let item_ids = [
+        add_new_item(&get_pocket, "https://getpocket.com/developer/docs/v3/add").await,
+        add_new_item(
+            &get_pocket,
+            "https://getpocket.com/developer/docs/v3/modify",
+        )
+        .await,
+    ];
+
+    let params: Vec<RequestArchive> = item_ids.iter().map(|id| RequestArchive::new(*id)).collect();
+    let resp_bulk_archive = get_pocket.bulk_archive(&params).await;
+    assert!(resp_bulk_archive.is_ok());
+    assert!(resp_bulk_archive.unwrap().is_success());
+
+    let params: Vec<RequestDelete> = item_ids.iter().map(|id| RequestDelete::new(*id)).collect();
+    let resp_bulk_delete = get_pocket.bulk_delete(&params).await;
+    assert!(resp_bulk_delete.is_ok());
+}
+
+#[test]
+async fn test_action_batch_serialize() {
+    let mut batch = ActionBatch::new().with_chunk_size(2);
+    batch
+        .push(RequestArchive::new(1))
+        .push(RequestAddTags::new(2, &["rust", "pocket"]))
+        .push(BulkRequestAdd::new(
+            "https://getpocket.com/developer/docs/v3/add",
+        ))
+        .push(RequestDelete::new(3).with_time(1348853312));
+
+    assert_eq!(batch.len(), 4);
+    assert_eq!(batch.chunks().count(), 2);
+
+    let actions = serde_json::to_value(batch.actions()).unwrap();
+    assert_eq!(
+        actions,
+        serde_json::json!([
+            { "action": "archive", "item_id": 1 },
+            { "action": "tags_add", "item_id": 2, "tags": "rust,pocket" },
+            { "action": "add", "url": "https://getpocket.com/developer/docs/v3/add" },
+            { "action": "delete", "item_id": 3, "time": 1348853312 },
+        ])
+    );
+}
+
+#[test]
+async fn test_send_batch() {
+    let get_pocket: GetPocket = test_helper::init_get_pocket().await;
+    let item_id = add_new_item(&get_pocket, "https://getpocket.com/developer/docs/v3/add").await;
+
+    let mut batch = ActionBatch::new();
+    batch
+        .push(RequestFavorite::new(item_id))
+        .push(RequestAddTags::new(item_id, &["rust"]))
+        .push(RequestArchive::new(item_id));
+
+    let resp_batch = get_pocket.send_batch(&batch).await.into_result();
+    assert!(resp_batch.is_ok());
+    assert_eq!(resp_batch.unwrap().len(), batch.len());
+
+    let _ = get_pocket.delete(item_id).await;
+}
+
+#[test]
+async fn test_send_batch_partial_failure() {
+    use getpocket::{HttpResponse, MemoryTransport};
+    use reqwest::StatusCode;
+
+    // Only the chunk starting with item 1 is accepted
+    let transport = MemoryTransport::new(|request| {
+        let params: serde_json::Value = serde_json::from_str(&request.text().unwrap()).unwrap();
+
+        Ok(match params["actions"][0]["item_id"].as_i64() {
+            Some(1) => HttpResponse::new(
+                StatusCode::OK,
+                r#"{"action_results": [true, false], "action_errors": [null, "Invalid item"], "status": 1}"#,
+            ),
+            _ => HttpResponse::new(StatusCode::BAD_REQUEST, ""),
+        })
+    });
+
+    let get_pocket = test_helper::memory_get_pocket(&transport);
+
+    let mut batch = ActionBatch::new().with_chunk_size(2);
+    batch.extend((1..=5).map(RequestArchive::new));
+
+    // Test case: The second chunk fails, the results of the first one are kept
+    let resp_batch = get_pocket.send_batch(&batch).await;
+    assert!(!resp_batch.is_complete());
+    assert_eq!(
+        resp_batch.results,
+        vec![
+            ActionResult {
+                is_success: true,
+                error: None,
+            },
+            ActionResult {
+                is_success: false,
+                error: Some("Invalid item".to_string()),
+            },
+        ]
+    );
+    assert_eq!(
+        resp_batch.error.as_ref().and_then(|err| err.status()),
+        Some(400)
+    );
+    assert_eq!(resp_batch.unsent(&batch).len(), 3);
+
+    // The third chunk is not sent after the failure
+    assert_eq!(transport.requests().len(), 2);
+    assert!(resp_batch.into_result().is_err());
+}
+
+#[test]
+async fn test_item_deserialize() {
+    let record: RecordItem = serde_json::from_str(
+        r#"{
+            "status": 1,
+            "complete": 1,
+            "error": null,
+            "since": 1700000000,
+            "list": {
+                "229279689": {
+                    "item_id": "229279689",
+                    "resolved_id": "229279689",
+                    "given_url": "https://getpocket.com/developer/docs/v3/add",
+                    "given_title": "",
+                    "favorite": "1",
+                    "status": "1",
+                    "resolved_title": "Pocket",
+                    "excerpt": "Adding items to Pocket is easy.",
+                    "is_article": "1",
+                    "is_index": "0",
+                    "has_video": "2",
+                    "has_image": "1",
+                    "word_count": "999",
+                    "lang": "en",
+                    "time_added": "1700000000",
+                    "time_read": "0",
+                    "time_to_read": 5,
+                    "top_image_url": "https://getpocket.com/image.jpg",
+                    "tags": { "rust": { "item_id": "229279689", "tag": "rust" } },
+                    "authors": { "42": { "item_id": "229279689", "author_id": "42", "name": "Pocket", "url": "" } },
+                    "images": { "1": { "item_id": "229279689", "image_id": "1", "src": "https://getpocket.com/image.jpg", "width": "0", "height": "0", "credit": "", "caption": "" } },
+                    "videos": [],
+                    "domain_metadata": { "name": "Pocket", "logo": "https://logo.clearbit.com/getpocket.com" }
+                },
+                "229279690": { "item_id": "229279690", "status": "2" }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let item = &record.list["229279689"];
+    assert_eq!(item.item_id, 229279689);
+    assert!(item.favorite);
+    assert!(item.has_video);
+    assert_eq!(item.status, ItemStatus::Archived);
+    assert_eq!(item.word_count, Some(999));
+    assert_eq!(item.time_added, Some(1700000000));
+    assert_eq!(item.time_read, None);
+    assert_eq!(item.tag_names().collect::<Vec<_>>(), vec!["rust"]);
+    assert_eq!(item.authors["42"].author_id, Some(42));
+    assert_eq!(item.images["1"].width, Some(0));
+    assert!(item.videos.is_empty());
+    assert_eq!(item.display_title(), Some("Pocket"));
+    assert_eq!(record.list["229279690"].status, ItemStatus::Deleted);
+
+    let empty: RecordItem = serde_json::from_str(
+        r#"{"status": 2, "complete": 1, "error": null, "since": 1, "list": []}"#,
+    )
+    .unwrap();
+    assert!(empty.list.is_empty());
+}
+
 pub async fn add_new_item(get_pocket: &GetPocket, url: &str) -> i64 {
     let resp = get_pocket.add_item(url).await.unwrap();
-    let item_id: i64 = resp
-        .item
-        .get("item_id")
+    let item_id: i64 = resp.item.item_id;
+    item_id
+}
+
+#[test]
+async fn test_base_url_mock_server() {
+    let base_url = test_helper::mock_server(
+        1,
+        200,
+        &[],
+        r#"{"status": 1, "complete": 1, "list": {}, "error": null, "since": 1700000000}"#,
+    );
+
+    let get_pocket = GetPocket::new(
+        "consumer_key".to_string(),
+        "redirect_uri".to_string(),
+        "access_token".to_string(),
+    )
+    .await
+    .unwrap()
+    .with_base_url(format!("{base_url}/"));
+
+    assert_eq!(get_pocket.base_url(), base_url);
+
+    let record = get_pocket.list(&ListQuery::new()).await.unwrap();
+    assert!(record.list.is_empty());
+    assert_eq!(record.since, 1700000000);
+}
+
+#[test]
+async fn test_error_headers_mock_server() {
+    let base_url = test_helper::mock_server(
+        1,
+        401,
+        &[
+            ("X-Error-Code", "152"),
+            ("X-Error", "Invalid consumer key."),
+        ],
+        "",
+    );
+
+    let get_pocket = GetPocket::new(
+        "consumer_key".to_string(),
+        "redirect_uri".to_string(),
+        "access_token".to_string(),
+    )
+    .await
+    .unwrap()
+    .with_base_url(base_url);
+
+    match get_pocket.list(&ListQuery::new()).await {
+        Err(getpocket::Error::Auth {
+            status,
+            code,
+            message,
+        }) => {
+            assert_eq!(status, Some(401));
+            assert_eq!(code, Some(152));
+            assert_eq!(message, "Invalid consumer key.");
+        }
+        res => panic!("unexpected response {res:?}"),
+    }
+}
+
+#[test]
+async fn test_builder() {
+    let base_url = test_helper::mock_server(
+        1,
+        200,
+        &[],
+        r#"{"status": 1, "complete": 1, "list": [], "error": null, "since": 1700000000}"#,
+    );
+
+    let get_pocket = GetPocket::builder()
+        .consumer_key("consumer_key")
+        .access_token("access_token")
+        .base_url(base_url)
+        .timeout(std::time::Duration::from_secs(10))
+        .connect_timeout(std::time::Duration::from_secs(5))
+        .user_agent("getpocket-tests")
+        .header("X-Request-Source", "tests")
+        .build()
+        .unwrap();
+
+    let record = get_pocket.list(&ListQuery::new()).await.unwrap();
+    assert!(record.list.is_empty());
+    assert_eq!(get_pocket.account(), None);
+
+    let get_pocket = GetPocket::builder()
+        .consumer_key("consumer_key")
+        .access_token("access_token")
+        .username("pocketuser")
+        .build()
+        .unwrap();
+    assert_eq!(get_pocket.account(), Some("pocketuser"));
+
+    // Invalid configurations are reported instead of panicking
+    assert!(GetPocket::builder()
+        .access_token("access_token")
+        .build()
+        .is_err());
+    assert!(GetPocket::builder()
+        .consumer_key("consumer_key")
+        .header("X-Invalid", "new\nline")
+        .build()
+        .is_err());
+    assert!(GetPocket::builder()
+        .consumer_key("consumer_key")
+        .client(reqwest::Client::new())
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .is_err());
+}
+
+#[test]
+async fn test_memory_transport() {
+    use getpocket::{HttpResponse, MemoryTransport};
+    use reqwest::StatusCode;
+
+    let transport = MemoryTransport::new(|request| {
+        let body = match request.url.contains("/v3/add") {
+            true => {
+                r#"{"item": {"item_id": "229279689", "given_url": "https://www.rust-lang.org/"}, "status": 1}"#
+            }
+            false => r#"{"action_results": [true], "status": 1}"#,
+        };
+
+        Ok(HttpResponse::new(StatusCode::OK, body))
+    });
+
+    let get_pocket = test_helper::memory_get_pocket(&transport);
+
+    let added = get_pocket
+        .add_item("https://www.rust-lang.org/")
+        .await
+        .unwrap();
+    assert_eq!(added.item.item_id, 229279689);
+
+    let archived = get_pocket.archive(229279689).await.unwrap();
+    assert!(archived.is_success);
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].url, "https://getpocket.com/v3/add");
+    assert!(requests[0]
+        .text()
         .unwrap()
-        .as_str()
+        .contains(r#""url":"https://www.rust-lang.org/""#));
+    assert_eq!(requests[1].url, "https://getpocket.com/v3/send");
+    assert!(requests[1]
+        .text()
         .unwrap()
-        .parse::<i64>()
+        .contains(r#""actions":[{"action":"archive","item_id":229279689"#));
+    assert_eq!(requests[1].headers["X-Accept"], "application/json");
+}
+
+#[test]
+async fn test_loopback_login() {
+    use getpocket::{auth::LoopbackLogin, HttpResponse, MemoryTransport};
+    use reqwest::StatusCode;
+    use std::io::{Read, Write};
+
+    let transport = MemoryTransport::new(|request| {
+        let body = match request.url.ends_with("/v3/oauth/request") {
+            true => r#"{"code": "request_token"}"#,
+            false => r#"{"access_token": "authorized_token", "username": "pocketuser"}"#,
+        };
+
+        Ok(HttpResponse::new(StatusCode::OK, body))
+    });
+
+    let get_pocket = test_helper::memory_get_pocket(&transport);
+
+    let get_pocket = LoopbackLogin::new()
+        .with_timeout(std::time::Duration::from_secs(10))
+        .login(get_pocket, |auth_url| {
+            assert!(auth_url.starts_with(
+                "https://getpocket.com/auth/authorize?request_token=request_token&redirect_uri="
+            ));
+
+            // The browser follows Pocket's redirect once the user authorized
+            let auth_url = reqwest::Url::parse(auth_url).unwrap();
+            let (_, redirect_uri) = auth_url
+                .query_pairs()
+                .find(|(name, _)| name == "redirect_uri")
+                .unwrap();
+            let redirect_uri = reqwest::Url::parse(&redirect_uri).unwrap();
+            let addr = format!("127.0.0.1:{}", redirect_uri.port().unwrap());
+            let path = redirect_uri.path().to_string();
+
+            std::thread::spawn(move || {
+                let mut stream = std::net::TcpStream::connect(addr).unwrap();
+                write!(stream, "GET {path} HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n").unwrap();
+                let mut response = String::new();
+                stream.read_to_string(&mut response).unwrap();
+                assert!(response.starts_with("HTTP/1.1 200 OK"));
+            });
+
+            Ok(())
+        })
+        .await
         .unwrap();
-    item_id
+
+    assert_eq!(
+        get_pocket.token.access_token.as_ref().map(Secret::expose),
+        Some("authorized_token")
+    );
+    assert_eq!(get_pocket.account(), Some("pocketuser"));
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[1]
+        .text()
+        .unwrap()
+        .contains(r#""code":"request_token""#));
+
+    // Nobody authorizes
+    let get_pocket = test_helper::memory_get_pocket(&transport);
+
+    let res = LoopbackLogin::new()
+        .with_timeout(std::time::Duration::from_millis(100))
+        .login(get_pocket, |_| Ok(()))
+        .await;
+    assert!(matches!(res, Err(getpocket::Error::Auth { .. })));
+}
+
+#[test]
+async fn test_two_step_authorization() {
+    use getpocket::{auth::AccessToken, HttpResponse, MemoryTransport};
+    use reqwest::StatusCode;
+
+    let transport = MemoryTransport::new(|request| {
+        let body = match request.url.ends_with("/v3/oauth/request") {
+            true => r#"{"code": "request_token", "state": null}"#,
+            false => r#"{"access_token": "access_token", "username": "pocketuser"}"#,
+        };
+
+        Ok(HttpResponse::new(StatusCode::OK, body))
+    });
+
+    let get_pocket = test_helper::memory_get_pocket(&transport);
+
+    let request = get_pocket
+        .request_authorization("https://example.com/callback")
+        .await
+        .unwrap();
+    assert_eq!(request.code.expose(), "request_token");
+    assert_eq!(
+        request.authorize_url,
+        "https://getpocket.com/auth/authorize?request_token=request_token&redirect_uri=https%3A%2F%2Fexample.com%2Fcallback"
+    );
+
+    let access_token = get_pocket
+        .complete_authorization(request.code.expose())
+        .await
+        .unwrap();
+    assert_eq!(
+        access_token,
+        AccessToken {
+            access_token: Secret::from("access_token"),
+            username: "pocketuser".to_string(),
+        }
+    );
+
+    let requests = transport.requests();
+    assert_eq!(
+        requests[0].text().unwrap(),
+        r#"{"consumer_key":"consumer_key","redirect_uri":"https://example.com/callback"}"#
+    );
+    assert_eq!(
+        requests[1].text().unwrap(),
+        r#"{"consumer_key":"consumer_key","code":"request_token"}"#
+    );
+
+    // The query string of the redirect URI is kept whole
+    let redirect_uri = "https://app.example.com/cb?state=x&u=1";
+    let request_with_query = get_pocket
+        .request_authorization(redirect_uri)
+        .await
+        .unwrap();
+    let authorize_url = reqwest::Url::parse(&request_with_query.authorize_url).unwrap();
+    let params: Vec<_> = authorize_url.query_pairs().into_owned().collect();
+    assert_eq!(
+        params,
+        vec![
+            ("request_token".to_string(), "request_token".to_string()),
+            ("redirect_uri".to_string(), redirect_uri.to_string()),
+        ]
+    );
+
+    // The request token is redacted in the authorization page too
+    let debug = format!("{request:?}");
+    assert!(debug.contains("request_token=[REDACTED]&redirect_uri="));
+    assert!(!debug.contains("request_token=request_token"));
+
+    // A response that cannot be decoded does not leak the request token or the access token
+    let transport = MemoryTransport::new(|request| {
+        let body = match request.url.ends_with("/v3/oauth/request") {
+            true => r#"{"code": "secret-request-token", "state": "#,
+            false => r#"{"access_token": "5678defg-5678-defg-5678-defg56"}"#,
+        };
+
+        Ok(HttpResponse::new(StatusCode::OK, body))
+    });
+    let get_pocket = test_helper::memory_get_pocket(&transport);
+
+    let err = get_pocket
+        .request_authorization("https://example.com/callback")
+        .await
+        .unwrap_err();
+    assert!(!format!("{err:?}").contains("secret-request-token"));
+    assert!(!err.to_string().contains("secret-request-token"));
+
+    let err = get_pocket
+        .complete_authorization("request_token")
+        .await
+        .unwrap_err();
+    assert!(!format!("{err:?}").contains("5678defg-5678-defg-5678-defg56"));
+    assert!(!err.to_string().contains("5678defg-5678-defg-5678-defg56"));
+}
+
+#[test]
+async fn test_token_store() {
+    use getpocket::{auth::AccessToken, FileTokenStore, MemoryTokenStore, TokenStore};
+
+    let token = AccessToken {
+        access_token: Secret::from("access_token"),
+        username: "pocketuser".to_string(),
+    };
+
+    let path = std::env::temp_dir().join(format!("getpocket_token_{}.json", std::process::id()));
+    let store = FileTokenStore::new(&path);
+
+    assert_eq!(store.load().unwrap(), None);
+    store.save(&token).unwrap();
+    assert_eq!(store.load().unwrap(), Some(token.clone()));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    store.clear().unwrap();
+    assert_eq!(store.load().unwrap(), None);
+    assert!(!path.exists());
+
+    // A stored token is reused without a login
+    let store = MemoryTokenStore::new();
+    store.save(&token).unwrap();
+
+    let get_pocket = GetPocket::from_store(
+        GetPocket::builder().consumer_key("consumer_key"),
+        &store,
+        |_| panic!("the stored token must be reused"),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        get_pocket.token.access_token.as_ref().map(Secret::expose),
+        Some("access_token")
+    );
+    assert_eq!(get_pocket.account(), Some("pocketuser"));
+}
+
+#[test]
+async fn test_secret_redacted() {
+    let get_pocket = GetPocket::builder()
+        .consumer_key("1234-abcd1234abcd1234abcd1234")
+        .access_token("5678defg-5678-defg-5678-defg56")
+        .build()
+        .unwrap();
+
+    let debug = format!("{get_pocket:?}");
+    assert!(!debug.contains("1234-abcd1234abcd1234abcd1234"));
+    assert!(!debug.contains("5678defg-5678-defg-5678-defg56"));
+
+    assert_eq!(get_pocket.consumer_key.to_string(), "[REDACTED]");
+    assert_eq!(
+        get_pocket.consumer_key.expose(),
+        "1234-abcd1234abcd1234abcd1234"
+    );
+    assert_eq!(
+        serde_json::to_string(&get_pocket.consumer_key).unwrap(),
+        r#""1234-abcd1234abcd1234abcd1234""#
+    );
+}
+
+#[test]
+async fn test_verify() {
+    use getpocket::{auth::CredentialStatus, HttpResponse, MemoryTransport};
+    use reqwest::{header::HeaderValue, StatusCode};
+
+    async fn verify(
+        status: StatusCode,
+        headers: &'static [(&'static str, &'static str)],
+    ) -> getpocket::Result<CredentialStatus> {
+        let transport = MemoryTransport::new(move |_| {
+            let mut res = HttpResponse::new(
+                status,
+                r#"{"status": 1, "complete": 1, "list": [], "error": null, "since": 1700000000}"#,
+            );
+            for (name, value) in headers {
+                res = res.with_header(name.parse().unwrap(), HeaderValue::from_static(value));
+            }
+
+            Ok(res)
+        });
+
+        test_helper::memory_get_pocket(&transport).verify().await
+    }
+
+    assert!(verify(StatusCode::OK, &[]).await.unwrap().is_valid());
+    assert!(matches!(
+        verify(StatusCode::UNAUTHORIZED, &[("X-Error-Code", "107")]).await,
+        Ok(CredentialStatus::InvalidAccessToken {
+            code: Some(107),
+            ..
+        })
+    ));
+    assert!(matches!(
+        verify(StatusCode::FORBIDDEN, &[("X-Error-Code", "152")]).await,
+        Ok(CredentialStatus::InvalidConsumerKey {
+            code: Some(152),
+            ..
+        })
+    ));
+    assert_eq!(
+        verify(
+            StatusCode::FORBIDDEN,
+            &[
+                ("X-Limit-User-Remaining", "0"),
+                ("X-Limit-User-Reset", "60")
+            ]
+        )
+        .await
+        .unwrap(),
+        CredentialStatus::RateLimited { reset: Some(60) }
+    );
+
+    // Test case: A 403 without an exhausted quota is not a rate limit
+    let err = verify(StatusCode::FORBIDDEN, &[]).await.unwrap_err();
+    assert_eq!(err.status(), Some(403));
+
+    let get_pocket = GetPocket::builder()
+        .consumer_key("consumer_key")
+        .build()
+        .unwrap();
+    assert_eq!(
+        get_pocket.verify().await.unwrap(),
+        CredentialStatus::MissingAccessToken
+    );
+}
+
+#[test]
+async fn test_from_config() {
+    let dir = std::env::temp_dir();
+    let path = dir.join(format!("getpocket_config_{}.toml", std::process::id()));
+
+    std::fs::write(
+        &path,
+        r#"
+            consumer_key = "consumer_key"
+            access_token = "access_token"
+            base_url = "http://127.0.0.1:8080/"
+            timeout_secs = 30
+
+            [retry]
+            max_attempts = 5
+        "#,
+    )
+    .unwrap();
+
+    let get_pocket = GetPocket::from_config(&path).unwrap();
+    assert_eq!(get_pocket.consumer_key.expose(), "consumer_key");
+    assert_eq!(
+        get_pocket.token.access_token.as_ref().map(Secret::expose),
+        Some("access_token")
+    );
+    assert_eq!(get_pocket.base_url(), "http://127.0.0.1:8080");
+
+    // Validation errors name the field
+    std::fs::write(&path, r#"access_token = "access_token""#).unwrap();
+    let err = GetPocket::from_config(&path).unwrap_err();
+    assert!(matches!(err, getpocket::Error::Config(_)));
+    assert!(err.to_string().contains("`consumer_key` is missing"));
+
+    std::fs::write(
+        &path,
+        r#"
+            consumer_key = "consumer_key"
+            timeout_secs = "thirty"
+        "#,
+    )
+    .unwrap();
+    let err = GetPocket::from_config(&path).unwrap_err();
+    assert!(err.to_string().contains("timeout_secs"));
+
+    std::fs::remove_file(&path).unwrap();
+
+    let err = GetPocket::from_config(&path).unwrap_err();
+    assert!(matches!(err, getpocket::Error::Config(_)));
+}
+
+/// Offline transport answering 503 to the first `failures` requests and 200 afterwards
+fn flaky_transport(failures: usize) -> getpocket::MemoryTransport {
+    use getpocket::HttpResponse;
+    use reqwest::StatusCode;
+
+    let failure = HttpResponse::new(StatusCode::SERVICE_UNAVAILABLE, "");
+
+    test_helper::failing_transport(failures, failure, |request| {
+        let body = match request.url.as_str() {
+            url if url.ends_with("/v3/add") => r#"{"item": {"item_id": "229279689"}, "status": 1}"#,
+            url if url.ends_with("/v3/oauth/authorize") => {
+                r#"{"access_token": "access_token", "username": "pocketuser"}"#
+            }
+            _ => r#"{"action_results": [true], "status": 1}"#,
+        };
+
+        HttpResponse::new(StatusCode::OK, body)
+    })
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_retry_policy() {
+    use getpocket::RetryPolicy;
+    use std::time::Duration;
+
+    let retry_policy = RetryPolicy::new()
+        .with_base_delay(Duration::from_millis(100))
+        .with_jitter(false);
+
+    // Test case: An idempotent action is sent again after a 503
+    let transport = flaky_transport(1);
+    let get_pocket =
+        test_helper::memory_get_pocket(&transport).with_retry_policy(retry_policy.clone());
+    assert!(get_pocket.archive(229279689).await.unwrap().is_success);
+    assert_eq!(transport.requests().len(), 2);
+
+    // Test case: Adding an item is not retried, it could create a duplicate
+    let transport = flaky_transport(1);
+    let get_pocket =
+        test_helper::memory_get_pocket(&transport).with_retry_policy(retry_policy.clone());
+    let res = get_pocket.add_item("https://www.rust-lang.org/").await;
+    assert!(matches!(res, Err(getpocket::Error::Maintenance { .. })));
+    assert_eq!(transport.requests().len(), 1);
+
+    // Test case: Neither is a batch with an add action
+    let transport = flaky_transport(1);
+    let get_pocket =
+        test_helper::memory_get_pocket(&transport).with_retry_policy(retry_policy.clone());
+    let res = get_pocket
+        .bulk_add(&[BulkRequestAdd::new("https://www.rust-lang.org/")])
+        .await;
+    assert!(res.is_err());
+    assert_eq!(transport.requests().len(), 1);
+
+    // Test case: Nor is the one-time exchange of the request token
+    let transport = flaky_transport(1);
+    let get_pocket =
+        test_helper::memory_get_pocket(&transport).with_retry_policy(retry_policy.clone());
+    let res = get_pocket.complete_authorization("request_token").await;
+    assert!(matches!(res, Err(getpocket::Error::Maintenance { .. })));
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].url.ends_with("/v3/oauth/authorize"));
+
+    // Test case: Unless the policy allows it
+    let transport = flaky_transport(1);
+    let get_pocket = test_helper::memory_get_pocket(&transport)
+        .with_retry_policy(retry_policy.clone().with_retry_non_idempotent(true));
+    let added = get_pocket
+        .add_item("https://www.rust-lang.org/")
+        .await
+        .unwrap();
+    assert_eq!(added.item.item_id, 229279689);
+    assert_eq!(transport.requests().len(), 2);
+
+    // Test case: The delays double up to `max_delay` and stop after `max_attempts`
+    let transport = flaky_transport(usize::MAX);
+    let get_pocket = test_helper::memory_get_pocket(&transport).with_retry_policy(
+        retry_policy
+            .with_max_attempts(4)
+            .with_max_delay(Duration::from_millis(150)),
+    );
+    let started = tokio::time::Instant::now();
+    assert!(get_pocket.archive(229279689).await.is_err());
+    assert_eq!(transport.requests().len(), 4);
+    // 100ms, then 200ms and 400ms capped to 150ms
+    assert_eq!(started.elapsed(), Duration::from_millis(400));
+}
+
+#[test]
+async fn test_retry_transport_errors() {
+    use async_trait::async_trait;
+    use getpocket::{HttpRequest, HttpResponse, HttpTransport, RetryPolicy};
+    use reqwest::StatusCode;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        sync::Arc,
+        time::Duration,
+    };
+
+    /// Fails the first request with a connection error of a closed port
+    #[derive(Debug, Default)]
+    struct Unreachable {
+        sent: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl HttpTransport for Unreachable {
+        async fn send(&self, _request: HttpRequest) -> getpocket::Result<HttpResponse> {
+            if self.sent.fetch_add(1, Ordering::SeqCst) == 0 {
+                let port = std::net::TcpListener::bind("127.0.0.1:0")
+                    .unwrap()
+                    .local_addr()
+                    .unwrap()
+                    .port();
+                reqwest::get(format!("http://127.0.0.1:{port}")).await?;
+            }
+
+            Ok(HttpResponse::new(
+                StatusCode::OK,
+                r#"{"action_results": [true], "status": 1}"#,
+            ))
+        }
+    }
+
+    let retry_policy = RetryPolicy::new()
+        .with_base_delay(Duration::from_millis(1))
+        .with_jitter(false);
+
+    let transport = Unreachable::default();
+    let sent = transport.sent.clone();
+    let get_pocket = GetPocket::builder()
+        .consumer_key("consumer_key")
+        .access_token("access_token")
+        .transport(transport)
+        .retry_policy(retry_policy.clone())
+        .build()
+        .unwrap();
+    assert!(get_pocket.archive(229279689).await.unwrap().is_success);
+    assert_eq!(sent.load(Ordering::SeqCst), 2);
+
+    let transport = Unreachable::default();
+    let sent = transport.sent.clone();
+    let get_pocket = GetPocket::builder()
+        .consumer_key("consumer_key")
+        .access_token("access_token")
+        .transport(transport)
+        .retry_policy(retry_policy.with_retry_transport_errors(false))
+        .build()
+        .unwrap();
+    assert!(matches!(
+        get_pocket.archive(229279689).await,
+        Err(getpocket::Error::Transport(_))
+    ));
+    assert_eq!(sent.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+async fn test_config_from_vars() {
+    use getpocket::Config;
+    use std::collections::HashMap;
+
+    fn from_vars(vars: &[(&str, &str)]) -> getpocket::Result<Config> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+
+        Config::from_vars(|name| vars.get(name).cloned())
+    }
+
+    let config = from_vars(&[
+        ("GET_POCKET_CONSUMER_KEY", "consumer_key"),
+        ("GET_POCKET_ACCESS_TOKEN", "access_token"),
+        ("GET_POCKET_TIMEOUT_SECS", " 30 "),
+        ("GET_POCKET_RETRY_MAX_ATTEMPTS", "5"),
+    ])
+    .unwrap();
+    assert_eq!(config.consumer_key, Some(Secret::from("consumer_key")));
+    assert_eq!(config.timeout_secs, Some(30));
+    assert_eq!(config.retry.unwrap().max_attempts, Some(5));
+
+    // Test case: The errors name the environment variable
+    for vars in [&[][..], &[("GET_POCKET_CONSUMER_KEY", "")][..]] {
+        let err = from_vars(vars).unwrap_err();
+        assert!(matches!(err, getpocket::Error::Config(_)));
+        assert!(err
+            .to_string()
+            .contains("The environment variable GET_POCKET_CONSUMER_KEY is not set or empty"));
+    }
+
+    let err = from_vars(&[
+        ("GET_POCKET_CONSUMER_KEY", "consumer_key"),
+        ("GET_POCKET_RETRY_BASE_DELAY_MS", "half a second"),
+    ])
+    .unwrap_err();
+    assert!(matches!(err, getpocket::Error::Config(_)));
+    assert!(err
+        .to_string()
+        .contains("GET_POCKET_RETRY_BASE_DELAY_MS is invalid"));
 }